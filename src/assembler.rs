@@ -0,0 +1,822 @@
+//! Assembleur compatible Octo
+//! Produit une ROM et la table des symboles (labels, adresse -> ligne source)
+//!
+//! Syntaxe reconnue : labels (`: nom`), `:alias`, `:const`, `:macro`, `:org`,
+//! `:byte`, `:call`, `:next`, `loop`/`while`/`again`, `if ... then`,
+//! `if ... begin ... else ... end`, toutes les instructions Chip-8, SCHIP et
+//! XO-CHIP, et les octets bruts. Si un label `main` existe, la ROM commence
+//! par `jump main`, comme avec Octo
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+const PROGRAM_START: usize = 0x200;
+const MAX_ADDRESS: usize = 0xFFFF;
+/// Garde-fou contre les macros récursives
+const MAX_MACRO_EXPANSIONS: usize = 10_000;
+
+/// Résultat de l'assemblage
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Program {
+    /// Octets à charger en 0x200
+    pub rom: Vec<u8>,
+    /// Labels et leur adresse
+    pub labels: BTreeMap<String, u16>,
+    /// Ligne source (à partir de 1) de chaque instruction ou octet de donnée, triée par adresse
+    pub lines: Vec<SourceLine>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourceLine {
+    pub address: u16,
+    pub line: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssembleError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ligne {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for AssembleError {}
+
+/// Assembler un source Octo
+pub fn assemble(source: &str) -> Result<Program, AssembleError> {
+    Assembler::new(tokenize(source)).run()
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    text: String,
+    line: usize,
+}
+
+/// Les tokens Octo sont séparés par des blancs, `#` commente jusqu'à la fin de ligne
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let code = line.split('#').next().unwrap_or("");
+        for word in code.split_whitespace() {
+            tokens.push(Token { text: word.to_string(), line: index + 1 });
+        }
+    }
+    tokens
+}
+
+/// Emplacement à compléter quand le label sera défini
+#[derive(Clone, Copy)]
+enum FixupKind {
+    /// 12 bits bas d'une instruction nnn
+    Address,
+    /// Mot complet de `i := long`
+    Long,
+}
+
+struct Fixup {
+    address: usize,
+    name: String,
+    kind: FixupKind,
+    line: usize,
+}
+
+struct Macro {
+    params: Vec<String>,
+    body: Vec<Token>,
+}
+
+/// Membre droit d'une comparaison
+#[derive(Clone, Copy)]
+enum Operand {
+    Register(u8),
+    Value(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    Greater,
+    LessOrEqual,
+    GreaterOrEqual,
+    Key,
+    NotKey,
+}
+
+impl Comparison {
+    fn negate(self) -> Self {
+        match self {
+            Comparison::Equal => Comparison::NotEqual,
+            Comparison::NotEqual => Comparison::Equal,
+            Comparison::Less => Comparison::GreaterOrEqual,
+            Comparison::GreaterOrEqual => Comparison::Less,
+            Comparison::Greater => Comparison::LessOrEqual,
+            Comparison::LessOrEqual => Comparison::Greater,
+            Comparison::Key => Comparison::NotKey,
+            Comparison::NotKey => Comparison::Key,
+        }
+    }
+}
+
+struct Condition {
+    register: u8,
+    comparison: Comparison,
+    operand: Option<Operand>,
+}
+
+/// Bloc `loop ... again` en cours : début et sauts `while` à compléter
+struct Loop {
+    start: usize,
+    exits: Vec<usize>,
+}
+
+struct Assembler {
+    tokens: Vec<Token>,
+    position: usize,
+    line: usize,
+    
+    memory: Vec<u8>,
+    address: usize,
+    highest: usize,
+    
+    labels: BTreeMap<String, u16>,
+    constants: HashMap<String, u16>,
+    aliases: HashMap<String, u8>,
+    macros: HashMap<String, Macro>,
+    expansions: usize,
+    
+    fixups: Vec<Fixup>,
+    loops: Vec<Loop>,
+    /// Sauts des `if ... begin` / `else` en attente de leur `end`
+    branches: Vec<usize>,
+    lines: Vec<SourceLine>,
+}
+
+impl Assembler {
+    fn new(tokens: Vec<Token>) -> Self {
+        Assembler {
+            tokens,
+            position: 0,
+            line: 1,
+            
+            memory: vec![0; MAX_ADDRESS + 1],
+            address: PROGRAM_START,
+            highest: PROGRAM_START,
+            
+            labels: BTreeMap::new(),
+            constants: HashMap::new(),
+            aliases: HashMap::new(),
+            macros: HashMap::new(),
+            expansions: 0,
+            
+            fixups: Vec::new(),
+            loops: Vec::new(),
+            branches: Vec::new(),
+            lines: Vec::new(),
+        }
+    }
+    
+    fn run(mut self) -> Result<Program, AssembleError> {
+        let has_main = self.tokens.windows(2).any(|pair| pair[0].text == ":" && pair[1].text == "main");
+        if has_main {
+            self.emit_jump_to(0x1000, "main")?;
+        }
+        
+        while self.position < self.tokens.len() {
+            self.statement()?;
+        }
+        
+        if let Some(open) = self.loops.last() {
+            return Err(self.error_at(self.line_of(open.start), "`loop` sans `again`"));
+        }
+        if !self.branches.is_empty() {
+            return Err(self.error("`begin` sans `end`"));
+        }
+        
+        for fixup in std::mem::take(&mut self.fixups) {
+            let target = *self.labels.get(&fixup.name)
+                .ok_or_else(|| self.error_at(fixup.line, &format!("label inconnu: {}", fixup.name)))?;
+            match fixup.kind {
+                FixupKind::Address => {
+                    if target > 0xFFF {
+                        return Err(self.error_at(fixup.line, &format!("{} (0x{:04X}) hors de portée de 12 bits", fixup.name, target)));
+                    }
+                    self.memory[fixup.address] |= (target >> 8) as u8;
+                    self.memory[fixup.address + 1] = target as u8;
+                },
+                FixupKind::Long => {
+                    self.memory[fixup.address] = (target >> 8) as u8;
+                    self.memory[fixup.address + 1] = target as u8;
+                },
+            }
+        }
+        
+        let mut lines = self.lines;
+        lines.sort_by_key(|line| line.address);
+        
+        Ok(Program {
+            rom: self.memory[PROGRAM_START..self.highest].to_vec(),
+            labels: self.labels,
+            lines,
+        })
+    }
+    
+    // ========== Lecture des tokens ==========
+    
+    fn next(&mut self) -> Result<String, AssembleError> {
+        let token = self.tokens.get(self.position)
+            .ok_or_else(|| self.error("fin de fichier inattendue"))?;
+        self.line = token.line;
+        self.position += 1;
+        Ok(token.text.clone())
+    }
+    
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(|token| token.text.as_str())
+    }
+    
+    fn expect(&mut self, expected: &str) -> Result<(), AssembleError> {
+        let token = self.next()?;
+        if token != expected {
+            return Err(self.error(&format!("`{}` attendu, trouvé `{}`", expected, token)));
+        }
+        Ok(())
+    }
+    
+    fn error(&self, message: &str) -> AssembleError {
+        self.error_at(self.line, message)
+    }
+    
+    fn error_at(&self, line: usize, message: &str) -> AssembleError {
+        AssembleError { line, message: message.to_string() }
+    }
+    
+    fn line_of(&self, address: usize) -> usize {
+        self.lines.iter()
+            .find(|line| line.address as usize == address)
+            .map_or(self.line, |line| line.line)
+    }
+    
+    // ========== Opérandes ==========
+    
+    fn parse_number(text: &str) -> Option<i32> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+        let value = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+            i32::from_str_radix(hex, 16).ok()?
+        } else if let Some(binary) = digits.strip_prefix("0b").or_else(|| digits.strip_prefix("0B")) {
+            i32::from_str_radix(binary, 2).ok()?
+        } else {
+            digits.parse().ok()?
+        };
+        Some(if negative { -value } else { value })
+    }
+    
+    /// Nombre, constante ou label déjà défini
+    fn value_of(&self, text: &str) -> Option<i32> {
+        Self::parse_number(text)
+            .or_else(|| self.constants.get(text).map(|&value| value as i32))
+            .or_else(|| self.labels.get(text).map(|&value| value as i32))
+    }
+    
+    fn register_of(&self, text: &str) -> Option<u8> {
+        if let Some(&register) = self.aliases.get(text) {
+            return Some(register);
+        }
+        let lower = text.to_ascii_lowercase();
+        let digit = lower.strip_prefix('v')?;
+        if digit.len() != 1 {
+            return None;
+        }
+        u8::from_str_radix(digit, 16).ok()
+    }
+    
+    fn register(&mut self) -> Result<u8, AssembleError> {
+        let token = self.next()?;
+        self.register_of(&token)
+            .ok_or_else(|| self.error(&format!("registre attendu, trouvé `{}`", token)))
+    }
+    
+    /// Valeur sur un octet ; les négatifs sont pris en complément à deux
+    fn byte(&mut self) -> Result<u8, AssembleError> {
+        let token = self.next()?;
+        let value = self.value_of(&token)
+            .ok_or_else(|| self.error(&format!("valeur attendue, trouvé `{}`", token)))?;
+        if !(-128..=255).contains(&value) {
+            return Err(self.error(&format!("{} ne tient pas sur un octet", value)));
+        }
+        Ok(value as u8)
+    }
+    
+    fn nibble(&mut self) -> Result<u8, AssembleError> {
+        let value = self.byte()?;
+        if value > 0xF {
+            return Err(self.error(&format!("{} ne tient pas sur 4 bits", value)));
+        }
+        Ok(value)
+    }
+    
+    // ========== Émission ==========
+    
+    fn emit_byte(&mut self, byte: u8) -> Result<(), AssembleError> {
+        if self.address > MAX_ADDRESS {
+            return Err(self.error("programme trop grand (64 KB maximum)"));
+        }
+        self.memory[self.address] = byte;
+        self.address += 1;
+        self.highest = self.highest.max(self.address);
+        Ok(())
+    }
+    
+    /// Octet de donnée, avec sa ligne source (il peut aussi s'agir de code écrit à la main)
+    fn emit_data(&mut self, byte: u8) -> Result<(), AssembleError> {
+        self.lines.push(SourceLine { address: self.address as u16, line: self.line });
+        self.emit_byte(byte)
+    }
+    
+    fn emit(&mut self, opcode: u16) -> Result<(), AssembleError> {
+        self.lines.push(SourceLine { address: self.address as u16, line: self.line });
+        self.emit_byte((opcode >> 8) as u8)?;
+        self.emit_byte(opcode as u8)
+    }
+    
+    /// Instruction `prefix | nnn` vers un label (éventuellement défini plus tard)
+    fn emit_jump_to(&mut self, prefix: u16, target: &str) -> Result<(), AssembleError> {
+        match self.value_of(target) {
+            Some(address) if (0..=0xFFF).contains(&address) => self.emit(prefix | address as u16),
+            Some(address) => Err(self.error(&format!("0x{:X} hors de portée de 12 bits", address))),
+            None => {
+                self.fixups.push(Fixup {
+                    address: self.address,
+                    name: target.to_string(),
+                    kind: FixupKind::Address,
+                    line: self.line,
+                });
+                self.emit(prefix)
+            },
+        }
+    }
+    
+    /// Compléter un `jump` émis plus tôt pour qu'il saute à l'adresse courante
+    fn patch_jump(&mut self, jump: usize) -> Result<(), AssembleError> {
+        if self.address > 0xFFF {
+            return Err(self.error("bloc au-delà de 0xFFF, hors de portée d'un jump"));
+        }
+        self.memory[jump] = 0x10 | (self.address >> 8) as u8;
+        self.memory[jump + 1] = self.address as u8;
+        Ok(())
+    }
+    
+    // ========== Instructions ==========
+    
+    fn statement(&mut self) -> Result<(), AssembleError> {
+        let token = self.next()?;
+        
+        if let Some(body) = self.expand_macro(&token)? {
+            let end = self.position;
+            self.tokens.splice(end..end, body);
+            return Ok(());
+        }
+        
+        match token.as_str() {
+            ":" => {
+                let name = self.next()?;
+                if self.labels.contains_key(&name) {
+                    return Err(self.error(&format!("label `{}` déjà défini", name)));
+                }
+                self.labels.insert(name, self.address as u16);
+            },
+            ":alias" => {
+                let name = self.next()?;
+                let register = self.register()?;
+                self.aliases.insert(name, register);
+            },
+            ":const" => {
+                let name = self.next()?;
+                let token = self.next()?;
+                let value = self.value_of(&token)
+                    .ok_or_else(|| self.error(&format!("valeur attendue, trouvé `{}`", token)))?;
+                self.constants.insert(name, value as u16);
+            },
+            ":macro" => self.define_macro()?,
+            ":org" => {
+                let token = self.next()?;
+                let address = self.value_of(&token)
+                    .filter(|address| (PROGRAM_START as i32..=MAX_ADDRESS as i32).contains(address))
+                    .ok_or_else(|| self.error(&format!("adresse invalide pour :org: `{}`", token)))?;
+                self.address = address as usize;
+            },
+            ":byte" => {
+                let byte = self.byte()?;
+                self.emit_data(byte)?;
+            },
+            ":call" => {
+                let target = self.next()?;
+                self.emit_jump_to(0x2000, &target)?;
+            },
+            ":next" => {
+                // Le label désigne le second octet de l'instruction suivante
+                let name = self.next()?;
+                self.labels.insert(name, (self.address + 1) as u16);
+            },
+            ":breakpoint" => {
+                self.next()?;
+            },
+            ":unpack" | ":calc" | ":stringmode" | ":assert" | ":monitor" | ":pointer" => {
+                return Err(self.error(&format!("`{}` n'est pas supporté", token)));
+            },
+            
+            "clear" => self.emit(0x00E0)?,
+            "return" | ";" => self.emit(0x00EE)?,
+            "exit" => self.emit(0x00FD)?,
+            "lores" => self.emit(0x00FE)?,
+            "hires" => self.emit(0x00FF)?,
+            "scroll-right" => self.emit(0x00FB)?,
+            "scroll-left" => self.emit(0x00FC)?,
+            "scroll-down" => {
+                let n = self.nibble()?;
+                self.emit(0x00C0 | n as u16)?;
+            },
+            "scroll-up" => {
+                let n = self.nibble()?;
+                self.emit(0x00D0 | n as u16)?;
+            },
+            "plane" => {
+                let n = self.nibble()?;
+                self.emit(0xF001 | (n as u16) << 8)?;
+            },
+            "audio" => self.emit(0xF002)?,
+            "bcd" => {
+                let x = self.register()?;
+                self.emit(0xF033 | (x as u16) << 8)?;
+            },
+            "save" | "load" => {
+                let x = self.register()?;
+                let range = self.peek() == Some("-");
+                if range {
+                    self.next()?;
+                    let y = self.register()?;
+                    let low = if token == "save" { 0x5002 } else { 0x5003 };
+                    self.emit(low | (x as u16) << 8 | (y as u16) << 4)?;
+                } else {
+                    let low = if token == "save" { 0xF055 } else { 0xF065 };
+                    self.emit(low | (x as u16) << 8)?;
+                }
+            },
+            "saveflags" => {
+                let x = self.register()?;
+                self.emit(0xF075 | (x as u16) << 8)?;
+            },
+            "loadflags" => {
+                let x = self.register()?;
+                self.emit(0xF085 | (x as u16) << 8)?;
+            },
+            "sprite" => {
+                let x = self.register()?;
+                let y = self.register()?;
+                let n = self.nibble()?;
+                self.emit(0xD000 | (x as u16) << 8 | (y as u16) << 4 | n as u16)?;
+            },
+            "jump" => {
+                let target = self.next()?;
+                self.emit_jump_to(0x1000, &target)?;
+            },
+            "jump0" => {
+                let target = self.next()?;
+                self.emit_jump_to(0xB000, &target)?;
+            },
+            "native" => {
+                let target = self.next()?;
+                self.emit_jump_to(0x0000, &target)?;
+            },
+            
+            "if" => self.if_statement()?,
+            "else" => {
+                let branch = self.branches.pop()
+                    .ok_or_else(|| self.error("`else` sans `if ... begin`"))?;
+                let jump = self.address;
+                self.emit(0x1000)?;
+                self.patch_jump(branch)?;
+                self.branches.push(jump);
+            },
+            "end" => {
+                let branch = self.branches.pop()
+                    .ok_or_else(|| self.error("`end` sans `if ... begin`"))?;
+                self.patch_jump(branch)?;
+            },
+            "loop" => self.loops.push(Loop { start: self.address, exits: Vec::new() }),
+            "while" => {
+                if self.loops.is_empty() {
+                    return Err(self.error("`while` hors d'un `loop`"));
+                }
+                let condition = self.condition()?;
+                // Sortir de la boucle quand la condition devient fausse
+                self.emit_skip(&condition, true)?;
+                let jump = self.address;
+                self.emit(0x1000)?;
+                if let Some(current) = self.loops.last_mut() {
+                    current.exits.push(jump);
+                }
+            },
+            "again" => {
+                let current = self.loops.pop()
+                    .ok_or_else(|| self.error("`again` sans `loop`"))?;
+                if current.start > 0xFFF {
+                    return Err(self.error("boucle au-delà de 0xFFF, hors de portée d'un jump"));
+                }
+                self.emit(0x1000 | current.start as u16)?;
+                for exit in current.exits {
+                    self.patch_jump(exit)?;
+                }
+            },
+            
+            "i" => self.i_assignment()?,
+            "delay" | "buzzer" | "pitch" => {
+                self.expect(":=")?;
+                let x = self.register()?;
+                let low = match token.as_str() {
+                    "delay" => 0xF015,
+                    "buzzer" => 0xF018,
+                    _ => 0xF03A,
+                };
+                self.emit(low | (x as u16) << 8)?;
+            },
+            
+            _ => {
+                if let Some(x) = self.register_of(&token) {
+                    self.register_assignment(x)?;
+                } else if self.labels.contains_key(&token) {
+                    // Un label déjà défini est aussi un appel, pas un octet
+                    self.emit_jump_to(0x2000, &token)?;
+                } else if let Some(value) = self.value_of(&token) {
+                    // Octet brut (données, sprites)
+                    if !(-128..=255).contains(&value) {
+                        return Err(self.error(&format!("{} ne tient pas sur un octet", value)));
+                    }
+                    self.emit_data(value as u8)?;
+                } else if token.starts_with(':') || !is_identifier(&token) {
+                    return Err(self.error(&format!("instruction inconnue: `{}`", token)));
+                } else {
+                    // Un label seul appelle le sous-programme
+                    self.emit_jump_to(0x2000, &token)?;
+                }
+            },
+        }
+        Ok(())
+    }
+    
+    fn i_assignment(&mut self) -> Result<(), AssembleError> {
+        let operator = self.next()?;
+        match operator.as_str() {
+            ":=" => {
+                let token = self.next()?;
+                match token.as_str() {
+                    "hex" => {
+                        let x = self.register()?;
+                        self.emit(0xF029 | (x as u16) << 8)
+                    },
+                    "bighex" => {
+                        let x = self.register()?;
+                        self.emit(0xF030 | (x as u16) << 8)
+                    },
+                    "long" => {
+                        let target = self.next()?;
+                        self.emit(0xF000)?;
+                        match self.value_of(&target) {
+                            Some(address) if (0..=MAX_ADDRESS as i32).contains(&address) => {
+                                self.emit_byte((address >> 8) as u8)?;
+                                self.emit_byte(address as u8)
+                            },
+                            Some(address) => Err(self.error(&format!("0x{:X} hors de la mémoire", address))),
+                            None => {
+                                self.fixups.push(Fixup {
+                                    address: self.address,
+                                    name: target,
+                                    kind: FixupKind::Long,
+                                    line: self.line,
+                                });
+                                self.emit_byte(0)?;
+                                self.emit_byte(0)
+                            },
+                        }
+                    },
+                    _ => self.emit_jump_to(0xA000, &token),
+                }
+            },
+            "+=" => {
+                let x = self.register()?;
+                self.emit(0xF01E | (x as u16) << 8)
+            },
+            _ => Err(self.error(&format!("opérateur invalide pour i: `{}`", operator))),
+        }
+    }
+    
+    fn register_assignment(&mut self, x: u8) -> Result<(), AssembleError> {
+        let vx = (x as u16) << 8;
+        let operator = self.next()?;
+        let token = self.next()?;
+        
+        // Membre droit registre : famille 8xyN
+        if let Some(y) = self.register_of(&token) {
+            let n = match operator.as_str() {
+                ":=" => 0x0,
+                "|=" => 0x1,
+                "&=" => 0x2,
+                "^=" => 0x3,
+                "+=" => 0x4,
+                "-=" => 0x5,
+                ">>=" => 0x6,
+                "=-" => 0x7,
+                "<<=" => 0xE,
+                _ => return Err(self.error(&format!("opérateur invalide: `{}`", operator))),
+            };
+            return self.emit(0x8000 | vx | (y as u16) << 4 | n);
+        }
+        
+        match (operator.as_str(), token.as_str()) {
+            (":=", "key") => self.emit(0xF00A | vx),
+            (":=", "delay") => self.emit(0xF007 | vx),
+            (":=", "random") => {
+                let mask = self.byte()?;
+                self.emit(0xC000 | vx | mask as u16)
+            },
+            (":=" | "+=" | "-=", _) => {
+                self.position -= 1;
+                let value = self.byte()?;
+                match operator.as_str() {
+                    ":=" => self.emit(0x6000 | vx | value as u16),
+                    "+=" => self.emit(0x7000 | vx | value as u16),
+                    // Pas de soustraction immédiate : on ajoute le complément
+                    _ => self.emit(0x7000 | vx | value.wrapping_neg() as u16),
+                }
+            },
+            _ => Err(self.error(&format!("opération invalide: `{} {}`", operator, token))),
+        }
+    }
+    
+    fn condition(&mut self) -> Result<Condition, AssembleError> {
+        let register = self.register()?;
+        let operator = self.next()?;
+        let comparison = match operator.as_str() {
+            "==" => Comparison::Equal,
+            "!=" => Comparison::NotEqual,
+            "<" => Comparison::Less,
+            ">" => Comparison::Greater,
+            "<=" => Comparison::LessOrEqual,
+            ">=" => Comparison::GreaterOrEqual,
+            "key" => return Ok(Condition { register, comparison: Comparison::Key, operand: None }),
+            "-key" => return Ok(Condition { register, comparison: Comparison::NotKey, operand: None }),
+            _ => return Err(self.error(&format!("comparaison invalide: `{}`", operator))),
+        };
+        
+        let token = self.next()?;
+        let operand = match self.register_of(&token) {
+            Some(y) => Operand::Register(y),
+            None => {
+                self.position -= 1;
+                Operand::Value(self.byte()?)
+            },
+        };
+        Ok(Condition { register, comparison, operand: Some(operand) })
+    }
+    
+    fn if_statement(&mut self) -> Result<(), AssembleError> {
+        let condition = self.condition()?;
+        let keyword = self.next()?;
+        match keyword.as_str() {
+            // L'instruction suivante n'est exécutée que si la condition est vraie
+            "then" => self.emit_skip(&condition, false),
+            "begin" => {
+                self.emit_skip(&condition, true)?;
+                self.branches.push(self.address);
+                self.emit(0x1000)
+            },
+            _ => Err(self.error(&format!("`then` ou `begin` attendu, trouvé `{}`", keyword))),
+        }
+    }
+    
+    /// Émettre le test qui saute l'instruction suivante quand la condition est fausse
+    /// (ou vraie si `negate`)
+    fn emit_skip(&mut self, condition: &Condition, negate: bool) -> Result<(), AssembleError> {
+        let comparison = if negate { condition.comparison.negate() } else { condition.comparison };
+        let vx = (condition.register as u16) << 8;
+        
+        match (comparison, condition.operand) {
+            (Comparison::Key, _) => self.emit(0xE0A1 | vx),
+            (Comparison::NotKey, _) => self.emit(0xE09E | vx),
+            (Comparison::Equal, Some(Operand::Value(kk))) => self.emit(0x4000 | vx | kk as u16),
+            (Comparison::NotEqual, Some(Operand::Value(kk))) => self.emit(0x3000 | vx | kk as u16),
+            (Comparison::Equal, Some(Operand::Register(y))) => self.emit(0x9000 | vx | (y as u16) << 4),
+            (Comparison::NotEqual, Some(Operand::Register(y))) => self.emit(0x5000 | vx | (y as u16) << 4),
+            (_, Some(operand)) => {
+                // Comparaisons d'ordre via VF, comme Octo : VF = gauche - droite,
+                // le flag vaut 1 quand gauche >= droite
+                let (left, right) = match comparison {
+                    Comparison::Less | Comparison::GreaterOrEqual => (Operand::Register(condition.register), operand),
+                    _ => (operand, Operand::Register(condition.register)),
+                };
+                match (left, right) {
+                    (Operand::Register(l), Operand::Register(r)) => {
+                        self.emit(0x8F00 | (l as u16) << 4)?;
+                        self.emit(0x8F05 | (r as u16) << 4)?;
+                    },
+                    (Operand::Value(kk), Operand::Register(r)) => {
+                        self.emit(0x6F00 | kk as u16)?;
+                        self.emit(0x8F05 | (r as u16) << 4)?;
+                    },
+                    // vf =- vl : VF = vl - kk
+                    (Operand::Register(l), Operand::Value(kk)) => {
+                        self.emit(0x6F00 | kk as u16)?;
+                        self.emit(0x8F07 | (l as u16) << 4)?;
+                    },
+                    (Operand::Value(_), Operand::Value(_)) => unreachable!(),
+                }
+                // < et > sont vrais avec un flag à 0, <= et >= avec un flag à 1
+                let skip_value = match comparison {
+                    Comparison::Less | Comparison::Greater => 1,
+                    _ => 0,
+                };
+                self.emit(0x3F00 | skip_value)
+            },
+            (_, None) => Err(self.error("opérande manquante")),
+        }
+    }
+    
+    // ========== Macros ==========
+    
+    fn define_macro(&mut self) -> Result<(), AssembleError> {
+        let name = self.next()?;
+        let mut params = Vec::new();
+        loop {
+            let token = self.next()?;
+            if token == "{" {
+                break;
+            }
+            params.push(token);
+        }
+        
+        let mut body = Vec::new();
+        let mut depth = 1;
+        loop {
+            let token = self.tokens.get(self.position).cloned()
+                .ok_or_else(|| self.error(&format!("macro `{}` sans `}}`", name)))?;
+            self.position += 1;
+            match token.text.as_str() {
+                "{" => depth += 1,
+                "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                },
+                _ => {},
+            }
+            body.push(token);
+        }
+        
+        self.macros.insert(name, Macro { params, body });
+        Ok(())
+    }
+    
+    /// Si `name` est une macro, lire ses arguments et retourner le corps substitué
+    fn expand_macro(&mut self, name: &str) -> Result<Option<Vec<Token>>, AssembleError> {
+        let Some(param_count) = self.macros.get(name).map(|m| m.params.len()) else {
+            return Ok(None);
+        };
+        
+        self.expansions += 1;
+        if self.expansions > MAX_MACRO_EXPANSIONS {
+            return Err(self.error(&format!("trop d'expansions de macro (`{}` récursive ?)", name)));
+        }
+        
+        let line = self.line;
+        let mut args = Vec::with_capacity(param_count);
+        for _ in 0..param_count {
+            args.push(self.next()?);
+        }
+        
+        let definition = &self.macros[name];
+        let body = definition.body.iter()
+            .map(|token| {
+                let text = definition.params.iter()
+                    .position(|param| *param == token.text)
+                    .map_or_else(|| token.text.clone(), |index| args[index].clone());
+                // Les instructions générées pointent vers la ligne d'appel
+                Token { text, line }
+            })
+            .collect();
+        Ok(Some(body))
+    }
+}
+
+fn is_identifier(token: &str) -> bool {
+    token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
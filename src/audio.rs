@@ -1,24 +1,42 @@
-//! Audio simple avec beep quand sound_timer > 0
+//! Audio synthétisé en Rust quand sound_timer > 0
+//! Buzzer carré classique, ou pattern 1 bit de 16 octets XO-CHIP (F002 / Fx3A)
 
-use wasm_bindgen::prelude::*;
+use std::collections::VecDeque;
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = console)]
-    fn log(s: &str);
-    
-    #[wasm_bindgen(js_namespace = ["window", "ferris8Audio"])]
-    fn playBeep(frequency: f32, volume: f32);
-    
-    #[wasm_bindgen(js_namespace = ["window", "ferris8Audio"])]
-    fn stopBeep();
-}
+use crate::savestate::{StateReader, StateWriter};
+
+/// Amplitude maximale des échantillons (le volume est appliqué en plus)
+const AMPLITUDE: f32 = 0.2;
+
+/// Pitch XO-CHIP par défaut : 4000 bits/s
+const DEFAULT_PITCH: u8 = 64;
+
+/// Nombre de frames 60 Hz mises en attente au maximum (une seconde)
+const MAX_QUEUED_FRAMES: f32 = 60.0;
 
 pub struct Audio {
     volume: f32,
     enabled: bool,
     frequency: f32,
     is_playing: bool,
+    
+    /// Pattern XO-CHIP de 128 bits, utilisé dès qu'un F002 a été exécuté
+    pattern: [u8; 16],
+    pattern_loaded: bool,
+    pitch: u8,
+    
+    /// Position dans le pattern (en bits) ou dans la période du buzzer
+    phase: f64,
+    sample_rate: f32,
+    /// Reste fractionnaire d'échantillons entre deux frames
+    pending_samples: f32,
+    samples: VecDeque<f32>,
+}
+
+impl Default for Audio {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Audio {
@@ -28,9 +46,29 @@ impl Audio {
             enabled: true,
             frequency: 440.0,
             is_playing: false,
+            
+            pattern: [0; 16],
+            pattern_loaded: false,
+            pitch: DEFAULT_PITCH,
+            
+            phase: 0.0,
+            sample_rate: 0.0,
+            pending_samples: 0.0,
+            samples: VecDeque::new(),
         }
     }
     
+    /// Revenir au buzzer par défaut et vider les échantillons en attente
+    pub fn reset(&mut self) {
+        self.is_playing = false;
+        self.pattern = [0; 16];
+        self.pattern_loaded = false;
+        self.pitch = DEFAULT_PITCH;
+        self.phase = 0.0;
+        self.pending_samples = 0.0;
+        self.samples.clear();
+    }
+    
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
     }
@@ -43,18 +81,97 @@ impl Audio {
         self.frequency = frequency.clamp(100.0, 2000.0);
     }
     
-    pub fn play_beep(&mut self) {
-        if self.enabled && !self.is_playing {
-            playBeep(self.frequency, self.volume);
-            self.is_playing = true;
+    /// Fréquence d'échantillonnage de la sortie, 0 désactive la synthèse
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate.max(0.0);
+        self.pending_samples = 0.0;
+        self.samples.clear();
+    }
+    
+    /// F002 - Charger le pattern audio de 16 octets
+    pub fn load_pattern(&mut self, pattern: &[u8]) {
+        let len = pattern.len().min(16);
+        self.pattern = [0; 16];
+        self.pattern[..len].copy_from_slice(&pattern[..len]);
+        self.pattern_loaded = true;
+    }
+    
+    /// Fx3A - Pitch du pattern
+    pub fn set_pitch(&mut self, pitch: u8) {
+        self.pitch = pitch;
+    }
+    
+    pub fn pattern(&self) -> &[u8; 16] {
+        &self.pattern
+    }
+    
+    pub fn pitch(&self) -> u8 {
+        self.pitch
+    }
+    
+    /// Vitesse de lecture du pattern en bits par seconde : 4000 * 2^((pitch - 64) / 48)
+    pub fn playback_rate(&self) -> f64 {
+        4000.0 * 2f64.powf((self.pitch as f64 - 64.0) / 48.0)
+    }
+    
+    /// Générer les échantillons d'une frame de 1/60 s, son actif ou non
+    pub fn render_frame(&mut self, active: bool) {
+        self.is_playing = active && self.enabled;
+        
+        if self.sample_rate <= 0.0 {
+            return;
+        }
+        
+        self.pending_samples += self.sample_rate / 60.0;
+        let count = self.pending_samples as usize;
+        self.pending_samples -= count as f32;
+        
+        for _ in 0..count {
+            let sample = if self.is_playing { self.next_sample() } else { 0.0 };
+            self.samples.push_back(sample);
+        }
+        
+        // Personne ne consomme : on garde seulement la dernière seconde
+        let max_samples = (self.sample_rate * MAX_QUEUED_FRAMES / 60.0) as usize;
+        while self.samples.len() > max_samples {
+            self.samples.pop_front();
+        }
+        
+        if !self.is_playing {
+            self.phase = 0.0;
         }
     }
     
-    pub fn stop_beep(&mut self) {
-        if self.is_playing {
-            stopBeep();
-            self.is_playing = false;
+    fn next_sample(&mut self) -> f32 {
+        let level = if self.pattern_loaded {
+            let bit = self.phase as usize % 128;
+            let high = (self.pattern[bit / 8] >> (7 - bit % 8)) & 1 == 1;
+            self.phase = (self.phase + self.playback_rate() / self.sample_rate as f64) % 128.0;
+            high
+        } else {
+            let high = self.phase < 0.5;
+            self.phase = (self.phase + self.frequency as f64 / self.sample_rate as f64) % 1.0;
+            high
+        };
+        
+        let amplitude = AMPLITUDE * self.volume;
+        if level { amplitude } else { -amplitude }
+    }
+    
+    /// Nombre d'échantillons prêts à être lus
+    pub fn samples_available(&self) -> usize {
+        self.samples.len()
+    }
+    
+    /// Remplir `out` avec les échantillons en attente (silence au-delà),
+    /// retourne le nombre d'échantillons réellement synthétisés
+    pub fn fill_samples(&mut self, out: &mut [f32]) -> usize {
+        let count = out.len().min(self.samples.len());
+        for (slot, sample) in out.iter_mut().zip(self.samples.drain(..count)) {
+            *slot = sample;
         }
+        out[count..].fill(0.0);
+        count
     }
     
     pub fn get_settings(&self) -> AudioSettings {
@@ -78,3 +195,24 @@ impl Audio {
     }
 }
 
+impl Audio {
+    /// Volume et activation restent des préférences de l'hôte, non sauvegardées
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        writer.bytes(&self.pattern);
+        writer.bool(self.pattern_loaded);
+        writer.u8(self.pitch);
+        writer.f64(self.phase);
+    }
+    
+    pub fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        reader.bytes(&mut self.pattern)?;
+        self.pattern_loaded = reader.bool()?;
+        self.pitch = reader.u8()?;
+        self.phase = reader.f64()?;
+        
+        self.is_playing = false;
+        self.pending_samples = 0.0;
+        self.samples.clear();
+        Ok(())
+    }
+}
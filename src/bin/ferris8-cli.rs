@@ -0,0 +1,379 @@
+//! Exécuteur en ligne de commande, sans navigateur
+//! Charge une ROM, la fait tourner N frames avec des touches scriptées,
+//! puis affiche l'écran en ASCII et/ou les registres
+//!
+//! Codes de sortie : 0 = frames exécutées, 1 = erreur d'utilisation,
+//! 2 = CPU arrêté par la ROM, 3 = trop d'erreurs CPU,
+//! 4 = trace différente de la référence (`--trace-diff`)
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+use ferris8::{Emulator, HaltReason};
+use ferris8::host::{set_logger, StderrLogger};
+
+const EXIT_COMPLETED: u8 = 0;
+const EXIT_USAGE: u8 = 1;
+const EXIT_HALTED: u8 = 2;
+const EXIT_ERRORS: u8 = 3;
+const EXIT_DIVERGED: u8 = 4;
+
+/// Durée par défaut, sans `--frames` ni `--movie`
+const DEFAULT_FRAMES: u32 = 600;
+
+/// Instructions par frame d'une ROM absente de la base, sans `--cycles`
+const DEFAULT_CYCLES: u32 = 10;
+
+/// Taille de la trace par défaut quand `--trace` ou `--trace-diff` est demandé
+const DEFAULT_TRACE_SIZE: usize = 100_000;
+
+const USAGE: &str = "\
+Usage: ferris8-cli <rom.ch8|source.8o> [options]
+
+Options:
+  --frames N          Nombre de frames 60 Hz à exécuter (défaut: 600,
+                      ou la durée du film avec --movie)
+  --cycles N          Instructions par frame (défaut: celui de la base de
+                      ROMs, sinon 10)
+  --platform NOM      chip8, schip ou xo-chip (défaut: chip8)
+  --quirks NOM        Preset de quirks: vip, schip, xo-chip, default
+  --no-database       Ne pas appliquer les réglages de la base de ROMs
+                      (implicite avec --platform ou --quirks)
+  --seed N            Graine du générateur aléatoire (Cxkk)
+  --rng NOM           Générateur aléatoire: lcg, xorshift, vip (défaut: lcg)
+  --policy NOM        Politique d'exécution: accurate, permissive, strict
+                      (défaut: permissive)
+  --keys SCRIPT       Événements clavier séparés par des virgules
+  --key-script FICHIER  Mêmes événements, un par ligne (# pour commenter)
+  --dump QUOI         screen, registers, all ou none (défaut: screen)
+  --symbols FICHIER   Table de symboles de la ROM (labels, lignes source)
+  --trace FICHIER     Écrire la trace d'exécution (binaire si .f8tr, texte sinon)
+  --trace-size N      Instructions gardées dans la trace (défaut: 100000)
+  --trace-diff FICHIER  Comparer la trace à une référence (texte ou .f8tr)
+  --record FICHIER    Enregistrer les touches dans un film (.f8mv)
+  --movie FICHIER     Rejouer un film : réglages, touches et vitesse viennent
+                      du film, --keys et --cycles sont ignorés
+  --verbose           Afficher les messages du cœur sur stderr
+  -h, --help          Afficher cette aide
+
+Un fichier .8o est assemblé avant d'être exécuté, avec ses symboles.
+Une ROM reconnue par son SHA-1 reçoit la plateforme, les quirks et la
+vitesse de la base intégrée.
+
+Événements clavier: FRAME:TOUCHE suivi de + (appui), - (relâchement)
+ou rien (appui pendant une frame). Ex: --keys 10:5+,40:5-,60:A";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Dump {
+    Screen,
+    Registers,
+    All,
+    Nothing,
+}
+
+struct KeyEvent {
+    frame: u32,
+    key: u8,
+    pressed: bool,
+}
+
+/// Ce que demande la ligne de commande
+enum Command {
+    Run(Box<Options>),
+    Help,
+}
+
+struct Options {
+    rom_path: String,
+    frames: Option<u32>,
+    cycles: Option<u32>,
+    platform: Option<String>,
+    quirks: Option<String>,
+    seed: Option<u64>,
+    rng: Option<String>,
+    policy: Option<String>,
+    symbols: Option<String>,
+    trace: Option<String>,
+    trace_size: usize,
+    trace_diff: Option<String>,
+    record: Option<String>,
+    movie: Option<String>,
+    events: Vec<KeyEvent>,
+    dump: Dump,
+    use_database: bool,
+    verbose: bool,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(env::args().skip(1).collect()) {
+        Ok(Command::Run(options)) => options,
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            return ExitCode::from(EXIT_COMPLETED);
+        },
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+    
+    match run(&options) {
+        Ok(code) => ExitCode::from(code),
+        Err(message) => {
+            eprintln!("ferris8-cli: {}", message);
+            ExitCode::from(EXIT_USAGE)
+        }
+    }
+}
+
+fn run(options: &Options) -> Result<u8, String> {
+    if options.verbose {
+        set_logger(Box::new(StderrLogger));
+    }
+    
+    let mut emulator = Emulator::new();
+    // Des réglages explicites l'emportent sur la base
+    emulator.set_rom_database_enabled(options.use_database && options.platform.is_none() && options.quirks.is_none());
+    if let Some(platform) = &options.platform
+        && !emulator.set_platform(platform)
+    {
+        return Err(format!("plateforme inconnue: {}", platform));
+    }
+    if let Some(quirks) = &options.quirks
+        && !emulator.set_quirks_preset(quirks)
+    {
+        return Err(format!("preset de quirks inconnu: {}", quirks));
+    }
+    if let Some(rng) = &options.rng
+        && !emulator.set_rng(rng)
+    {
+        return Err(format!("générateur aléatoire inconnu: {}", rng));
+    }
+    if let Some(seed) = options.seed {
+        emulator.set_seed(seed);
+    }
+    if let Some(policy) = &options.policy
+        && !emulator.set_execution_policy(policy)
+    {
+        return Err(format!("politique d'exécution inconnue: {}", policy));
+    }
+    if options.rom_path.ends_with(".8o") {
+        let source = fs::read_to_string(&options.rom_path)
+            .map_err(|e| format!("impossible de lire {}: {}", options.rom_path, e))?;
+        let file_name = Path::new(&options.rom_path).file_name()
+            .map_or(options.rom_path.clone(), |name| name.to_string_lossy().into_owned());
+        emulator.load_source(&source, &file_name)?;
+    } else {
+        let rom = fs::read(&options.rom_path)
+            .map_err(|e| format!("impossible de lire {}: {}", options.rom_path, e))?;
+        if !emulator.load_rom(&rom) {
+            return Err(format!("ROM invalide ou trop grande ({} octets, max {})", rom.len(), emulator.max_rom_size()));
+        }
+    }
+    if let Some(path) = &options.symbols {
+        let symbols = fs::read_to_string(path)
+            .map_err(|e| format!("impossible de lire {}: {}", path, e))?;
+        emulator.load_symbols(&symbols)?;
+    }
+    if options.trace.is_some() || options.trace_diff.is_some() {
+        emulator.set_trace(options.trace_size);
+    }
+    if let Some(path) = &options.movie {
+        let movie = fs::read(path).map_err(|e| format!("impossible de lire {}: {}", path, e))?;
+        emulator.play_movie(&movie)?;
+    }
+    if options.record.is_some() {
+        emulator.start_recording()?;
+    }
+    
+    let frames = options.frames.unwrap_or(match options.movie {
+        Some(_) => emulator.get_movie_length(),
+        None => DEFAULT_FRAMES,
+    });
+    let cycles = options.cycles.unwrap_or(match emulator.get_rom_cycles_per_frame() {
+        0 => DEFAULT_CYCLES,
+        recommended => recommended,
+    });
+    emulator.start();
+    let mut frames_run = 0;
+    for frame in 0..frames {
+        for event in options.events.iter().filter(|e| e.frame == frame) {
+            if event.pressed {
+                emulator.key_down(event.key);
+            } else {
+                emulator.key_up(event.key);
+            }
+        }
+        
+        emulator.run_frame(cycles);
+        frames_run += 1;
+        
+        if emulator.is_halted() {
+            break;
+        }
+    }
+    
+    let cpu = emulator.cpu();
+    if matches!(options.dump, Dump::Screen | Dump::All) {
+        print!("{}", cpu.display.to_text('#', '.'));
+    }
+    if matches!(options.dump, Dump::Registers | Dump::All) {
+        println!("{}", cpu.get_debug_info());
+        println!("{}", cpu.get_stats());
+    }
+    
+    if let Some(path) = &options.trace {
+        let trace = if path.ends_with(".f8tr") {
+            emulator.export_trace()
+        } else {
+            emulator.export_trace_text(0).into_bytes()
+        };
+        fs::write(path, trace).map_err(|e| format!("impossible d'écrire {}: {}", path, e))?;
+    }
+    if let Some(path) = &options.record {
+        fs::write(path, emulator.stop_recording()).map_err(|e| format!("impossible d'écrire {}: {}", path, e))?;
+    }
+    if let Some(path) = &options.trace_diff {
+        let reference = fs::read(path).map_err(|e| format!("impossible de lire {}: {}", path, e))?;
+        if let Some(divergence) = emulator.diff_trace(&reference)? {
+            eprintln!("{}", divergence);
+            return Ok(EXIT_DIVERGED);
+        }
+    }
+    
+    let code = match emulator.get_halt_reason() {
+        None => EXIT_COMPLETED,
+        Some(reason) => {
+            let message = emulator.get_halt_message().unwrap_or_default();
+            eprintln!("CPU arrêté: {} (frame {})", message, frames_run);
+            let call_stack = emulator.describe_call_stack();
+            if !call_stack.is_empty() {
+                eprint!("Pile d'appels:\n{}", call_stack);
+            }
+            if reason == HaltReason::ErrorLimit { EXIT_ERRORS } else { EXIT_HALTED }
+        },
+    };
+    Ok(code)
+}
+
+fn parse_args(args: Vec<String>) -> Result<Command, String> {
+    let mut options = Options {
+        rom_path: String::new(),
+        frames: None,
+        cycles: None,
+        platform: None,
+        quirks: None,
+        seed: None,
+        rng: None,
+        policy: None,
+        symbols: None,
+        trace: None,
+        trace_size: DEFAULT_TRACE_SIZE,
+        trace_diff: None,
+        record: None,
+        movie: None,
+        events: Vec::new(),
+        dump: Dump::Screen,
+        use_database: true,
+        verbose: false,
+    };
+    
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} attend une valeur", name));
+        
+        match arg.as_str() {
+            "--frames" => options.frames = Some(parse_number(&value("--frames")?)?),
+            "--cycles" => options.cycles = Some(parse_number(&value("--cycles")?)?),
+            "--platform" => options.platform = Some(value("--platform")?),
+            "--quirks" => options.quirks = Some(value("--quirks")?),
+            "--seed" => {
+                let seed = value("--seed")?;
+                options.seed = Some(seed.parse().map_err(|_| format!("graine invalide: {}", seed))?);
+            },
+            "--rng" => options.rng = Some(value("--rng")?),
+            "--policy" => options.policy = Some(value("--policy")?),
+            "--symbols" => options.symbols = Some(value("--symbols")?),
+            "--trace" => options.trace = Some(value("--trace")?),
+            "--trace-size" => options.trace_size = parse_number(&value("--trace-size")?)? as usize,
+            "--trace-diff" => options.trace_diff = Some(value("--trace-diff")?),
+            "--record" => options.record = Some(value("--record")?),
+            "--movie" => options.movie = Some(value("--movie")?),
+            "--keys" => {
+                let script = value("--keys")?;
+                options.events.extend(parse_key_script(&script.replace(',', "\n"))?);
+            },
+            "--key-script" => {
+                let path = value("--key-script")?;
+                let script = fs::read_to_string(&path)
+                    .map_err(|e| format!("impossible de lire {}: {}", path, e))?;
+                options.events.extend(parse_key_script(&script)?);
+            },
+            "--dump" => {
+                options.dump = match value("--dump")?.as_str() {
+                    "screen" => Dump::Screen,
+                    "registers" => Dump::Registers,
+                    "all" => Dump::All,
+                    "none" => Dump::Nothing,
+                    other => return Err(format!("--dump inconnu: {}", other)),
+                };
+            },
+            "--no-database" => options.use_database = false,
+            "--verbose" => options.verbose = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            _ if arg.starts_with("--") => return Err(format!("option inconnue: {}", arg)),
+            _ if options.rom_path.is_empty() => options.rom_path = arg,
+            _ => return Err(format!("argument en trop: {}", arg)),
+        }
+    }
+    
+    if options.rom_path.is_empty() {
+        return Err(String::from("aucune ROM indiquée"));
+    }
+    if options.record.is_some() && options.movie.is_some() {
+        return Err(String::from("--record et --movie sont incompatibles"));
+    }
+    Ok(Command::Run(Box::new(options)))
+}
+
+fn parse_number(text: &str) -> Result<u32, String> {
+    text.parse().map_err(|_| format!("nombre invalide: {}", text))
+}
+
+/// Une entrée par ligne : FRAME:TOUCHE[+|-]
+fn parse_key_script(script: &str) -> Result<Vec<KeyEvent>, String> {
+    let mut events = Vec::new();
+    
+    for line in script.lines() {
+        let entry = line.split('#').next().unwrap_or("").trim();
+        if entry.is_empty() {
+            continue;
+        }
+        
+        let (frame, key) = entry.split_once(':')
+            .ok_or_else(|| format!("événement invalide: {}", entry))?;
+        let frame = parse_number(frame.trim())?;
+        
+        let (key, action) = match key.trim() {
+            k if k.ends_with('+') => (&k[..k.len() - 1], Some(true)),
+            k if k.ends_with('-') => (&k[..k.len() - 1], Some(false)),
+            k => (k, None),
+        };
+        let key = u8::from_str_radix(key, 16)
+            .ok()
+            .filter(|&k| k <= 0xF)
+            .ok_or_else(|| format!("touche invalide: {}", key))?;
+        
+        match action {
+            Some(pressed) => events.push(KeyEvent { frame, key, pressed }),
+            None => {
+                events.push(KeyEvent { frame, key, pressed: true });
+                events.push(KeyEvent { frame: frame + 1, key, pressed: false });
+            }
+        }
+    }
+    
+    Ok(events)
+}
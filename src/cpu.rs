@@ -1,11 +1,29 @@
 //! CPU Chip-8 avec architecture classique
 //! 16 registres V0-VF, registre I, PC, SP et timers
 
-use crate::{Memory, Display, Input, Audio};
+use std::collections::VecDeque;
+
+use crate::{Memory, Display, Input, Audio, Quirks, Platform};
+use crate::host::log;
+use crate::rng::Rng;
+use crate::disasm::{self, Syntax};
+use crate::savestate::{self, StateReader, StateWriter};
+use crate::symbols::SymbolMap;
+use crate::trace::{TraceEntry, Tracer};
+use crate::error::{CpuError, CpuErrorKind, HaltReason};
+use crate::policy::ExecutionPolicy;
 
-const MAX_MEMORY: u16 = 0x1000;
 const PROGRAM_START: u16 = 0x200;
-const MAX_STACK_SIZE: u8 = 16;
+/// Profondeur de pile avant tout choix de plateforme
+const DEFAULT_STACK_DEPTH: usize = 16;
+/// Le pointeur de pile tient sur un octet
+const MAX_STACK_DEPTH: usize = u8::MAX as usize;
+
+/// Au-delà de ce nombre d'erreurs le CPU s'arrête
+pub const MAX_ERRORS: u32 = 10;
+
+/// Nombre d'erreurs gardées dans l'historique
+const ERROR_LOG_SIZE: usize = 64;
 
 pub struct Cpu {
     pub v: [u8; 16],
@@ -21,12 +39,43 @@ pub struct Cpu {
     pub input: Input,
     pub audio: Audio,
     
-    pub stack: [u16; 16],
+    /// Adresses de retour, une case par niveau (la taille est la profondeur de pile)
+    pub stack: Vec<u16>,
+    
+    pub quirks: Quirks,
+    pub platform: Platform,
+    /// Réaction aux adresses invalides (réglage de l'émulateur, hors sauvegardes)
+    policy: ExecutionPolicy,
+    
+    /// Générateur de Cxkk, remis à sa graine à chaque reset
+    pub rng: Rng,
+    
+    /// Flags RPL de la HP48 (Fx75/Fx85), conservés entre deux resets
+    pub rpl: [u8; 16],
     
     pub draw_flag: bool,
-    pub halted: bool,
+    pub vblank: bool,
+    /// Raison de l'arrêt, None tant que le CPU tourne
+    pub halt_reason: Option<HaltReason>,
     pub error_count: u32,
     pub cycle_count: u64,
+    
+    /// Symboles de debug du programme chargé (hors sauvegardes)
+    pub symbols: SymbolMap,
+    /// Adresse de l'instruction en cours, pour situer les erreurs
+    instruction_address: u16,
+    /// Opcode de l'instruction en cours
+    instruction: u16,
+    /// Dernières erreurs d'exécution, la plus ancienne en tête
+    errors: VecDeque<CpuError>,
+    /// Trace des dernières instructions (désactivée par défaut, hors sauvegardes)
+    pub tracer: Tracer,
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Cpu {
@@ -45,12 +94,27 @@ impl Cpu {
             input: Input::new(),
             audio: Audio::new(),
             
-            stack: [0; 16],
+            stack: vec![0; DEFAULT_STACK_DEPTH],
+            
+            quirks: Quirks::default(),
+            platform: Platform::default(),
+            policy: ExecutionPolicy::default(),
+            
+            rng: Rng::default(),
+            
+            rpl: [0; 16],
             
             draw_flag: false,
-            halted: false,
+            vblank: true,
+            halt_reason: None,
             error_count: 0,
             cycle_count: 0,
+            
+            symbols: SymbolMap::new(),
+            instruction_address: PROGRAM_START,
+            instruction: 0,
+            errors: VecDeque::new(),
+            tracer: Tracer::new(),
         };
         
         cpu.memory.load_fontset();
@@ -68,82 +132,179 @@ impl Cpu {
         
         self.memory.clear();
         self.memory.load_fontset();
-        self.display.clear();
+        self.display.select_planes(1);
+        self.display.set_high_resolution(false);
         self.input.clear();
+        self.audio.reset();
         
-        self.stack = [0; 16];
+        self.stack.fill(0);
+        self.rng.reseed();
         
         self.draw_flag = false;
-        self.halted = false;
+        self.vblank = true;
+        self.halt_reason = None;
         self.error_count = 0;
         self.cycle_count = 0;
+        self.errors.clear();
+        self.tracer.clear();
     }
     
-    pub fn load_rom(&mut self, rom_data: &[u8]) {
-        self.memory.load_rom(rom_data);
+    /// Charger une ROM, false si elle est vide ou trop grande pour la mémoire courante
+    pub fn load_rom(&mut self, rom_data: &[u8]) -> bool {
+        if !self.memory.load_rom(rom_data) {
+            return false;
+        }
         
         self.pc = PROGRAM_START;
-        self.halted = false;
+        self.halt_reason = None;
         self.error_count = 0;
         self.cycle_count = 0;
+        self.errors.clear();
+        self.rng.reseed();
+        self.tracer.clear();
         self.draw_flag = true;
+        true
     }
     
     pub fn cycle(&mut self) {
-        if self.halted {
-            return;
-        }
-        
-        if self.error_count > 10 {
-            self.halted = true;
+        if self.is_halted() {
             return;
         }
         
         self.cycle_count += 1;
         
+        // Une erreur de PC est attribuée à l'instruction précédente, qui l'a produit
         if !self.validate_pc() {
             return;
         }
+        self.instruction_address = self.pc;
+        self.instruction = self.read_word(self.pc);
         
-        let instruction = self.fetch_instruction();
+        let registers_before = self.tracer.is_enabled().then_some(self.v);
+        let Some(instruction) = self.fetch_instruction() else {
+            return;
+        };
         self.execute_instruction(instruction);
-        self.update_timers();
+        
+        if let Some(before) = registers_before {
+            let entry = TraceEntry::new(self.cycle_count, self.instruction_address, instruction, self.i, &before, &self.v);
+            self.tracer.record(entry);
+        }
     }
     
+    /// Exécuter une frame : `cycles` instructions puis un tick des timers à 60 Hz
+    pub fn run_frame(&mut self, cycles: u32) {
+        for _ in 0..cycles {
+            if self.is_halted() {
+                break;
+            }
+            self.cycle();
+        }
+        self.tick_timers();
+    }
+    
+    /// Ramener le PC dans la mémoire selon la politique, false si rien ne doit être exécuté
     fn validate_pc(&mut self) -> bool {
-        if self.pc >= MAX_MEMORY {
-            self.pc = PROGRAM_START;
-            self.error_count += 1;
-            return false;
+        let address = self.pc;
+        
+        if self.policy == ExecutionPolicy::Accurate {
+            self.pc = self.wrap(address);
+            return true;
         }
         
-        if self.pc < PROGRAM_START {
-            self.pc = PROGRAM_START;
-            self.error_count += 1;
-            return false;
+        let out_of_range = address as usize >= self.memory.size() || address < PROGRAM_START;
+        if !out_of_range && address.is_multiple_of(2) {
+            return true;
         }
         
-        if self.pc % 2 != 0 {
-            self.pc = self.pc & 0xFFFE;
-            self.error_count += 1;
+        self.report(CpuErrorKind::InvalidPc { address });
+        if self.policy == ExecutionPolicy::Strict {
+            return false;
         }
         
+        if out_of_range {
+            self.pc = PROGRAM_START;
+            return false;
+        }
+        self.pc &= 0xFFFE;
         true
     }
     
-    fn fetch_instruction(&mut self) -> u16 {
-        if self.pc + 1 >= MAX_MEMORY {
-            self.error_count += 1;
-            self.halted = true;
-            return 0x1200;
+    fn fetch_instruction(&mut self) -> Option<u16> {
+        if self.policy != ExecutionPolicy::Accurate && self.pc as usize + 1 >= self.memory.size() {
+            self.halt(HaltReason::EndOfMemory);
+            return None;
         }
         
-        let high_byte = self.memory.read_byte(self.pc) as u16;
-        let low_byte = self.memory.read_byte(self.pc + 1) as u16;
-        let instruction = (high_byte << 8) | low_byte;
+        let instruction = self.read_word(self.pc);
         
-        self.pc += 2;
-        instruction
+        self.pc = self.pc.wrapping_add(2);
+        Some(instruction)
+    }
+    
+    fn read_word(&self, address: u16) -> u16 {
+        let high_byte = self.memory.peek_byte(self.wrap(address)) as u16;
+        let low_byte = self.memory.peek_byte(self.wrap(address.wrapping_add(1))) as u16;
+        (high_byte << 8) | low_byte
+    }
+    
+    /// Adresse ramenée dans la mémoire (la taille est une puissance de 2)
+    fn wrap(&self, address: u16) -> u16 {
+        (address as usize % self.memory.size()) as u16
+    }
+    
+    /// Lire `length` octets à partir de `address`, en bouclant sur la fin de la mémoire
+    fn read_block(&self, address: u16, length: u16) -> Vec<u8> {
+        (0..length)
+            .map(|offset| self.memory.read_byte(self.wrap(address.wrapping_add(offset))))
+            .collect()
+    }
+    
+    /// Écrire un octet ; en politique stricte, écrire dans une zone protégée est une erreur
+    fn store(&mut self, address: u16, value: u8) {
+        let address = self.wrap(address);
+        if self.policy == ExecutionPolicy::Strict && self.memory.is_write_protected(address) {
+            self.report(CpuErrorKind::ProtectedWrite { address });
+            return;
+        }
+        self.memory.write_byte(address, value);
+    }
+    
+    /// Un accès de `length` octets en `address` tient-il en mémoire ? En politique
+    /// Accurate l'accès boucle sur le début de la mémoire, sinon c'est une erreur
+    fn check_range(&mut self, address: u16, length: u16) -> bool {
+        if self.policy == ExecutionPolicy::Accurate || address as usize + length as usize <= self.memory.size() {
+            return true;
+        }
+        self.report(CpuErrorKind::MemoryOutOfBounds { address, length });
+        false
+    }
+    
+    /// Destination d'un saut : repliée en politique Accurate, vérifiée sinon
+    fn jump_target(&mut self, target: u16) -> Option<u16> {
+        if self.policy == ExecutionPolicy::Accurate {
+            return Some(self.wrap(target));
+        }
+        if !self.is_valid_program_address(target) {
+            self.report(CpuErrorKind::InvalidJump { target });
+            return None;
+        }
+        Some(target)
+    }
+    
+    /// Refaire l'instruction courante au prochain cycle ; PC a pu boucler en 0
+    /// sur une mémoire de 64 Ko
+    fn repeat_instruction(&mut self) {
+        self.pc = self.wrap(self.pc.wrapping_sub(2));
+    }
+    
+    /// Sauter l'instruction suivante (4 octets si c'est un F000 nnnn XO-CHIP)
+    fn skip_next_instruction(&mut self) {
+        if self.platform.has_xo_opcodes() && self.read_word(self.pc) == 0xF000 {
+            self.pc = self.pc.wrapping_add(4);
+        } else {
+            self.pc = self.pc.wrapping_add(2);
+        }
     }
     
     fn execute_instruction(&mut self, instruction: u16) {
@@ -164,22 +325,24 @@ impl Cpu {
             0xD000 => self.execute_dxyn(instruction),
             0xE000 => self.execute_exxx(instruction),
             0xF000 => self.execute_fxxx(instruction),
-            _ => {
-                self.error_count += 1;
-            }
+            _ => unreachable!(),
         }
     }
     
-    fn update_timers(&mut self) {
+    /// Décrémenter les timers, à appeler exactement une fois par frame (60 Hz)
+    pub fn tick_timers(&mut self) {
+        self.vblank = true;
+        self.rng.tick();
+        self.input.end_frame();
+        
         if self.delay_timer > 0 {
             self.delay_timer -= 1;
         }
         
+        // Le son couvre toute la frame pendant laquelle sound_timer > 0
+        self.audio.render_frame(self.sound_timer > 0);
         if self.sound_timer > 0 {
             self.sound_timer -= 1;
-            self.audio.play_beep();
-        } else if self.audio.is_playing() {
-            self.audio.stop_beep();
         }
     }
     
@@ -192,44 +355,73 @@ impl Cpu {
             },
             0x00EE => {
                 if self.sp == 0 {
-                    self.halted = true;
+                    self.report(CpuErrorKind::StackUnderflow);
+                    self.halt(HaltReason::StackUnderflow);
                     return;
                 }
                 self.sp -= 1;
                 self.pc = self.stack[self.sp as usize];
                 
-                if !self.is_valid_program_address(self.pc) {
-                    self.halted = true;
+                if self.policy != ExecutionPolicy::Accurate && !self.is_valid_program_address(self.pc) {
+                    self.report(CpuErrorKind::InvalidJump { target: self.pc });
+                    self.halt(HaltReason::InvalidReturn);
                 }
             },
             0x0000 => {
-                self.halted = true;
+                self.halt(HaltReason::Opcode0000);
+            },
+            _ if self.platform.has_schip_opcodes() => self.execute_schip_0xxx(instruction),
+            _ => {} // SYS ignored
+        }
+    }
+    
+    /// Extensions SCHIP : scrolling, sortie et changement de résolution
+    fn execute_schip_0xxx(&mut self, instruction: u16) {
+        match instruction {
+            0x00C0..=0x00CF => { // SCD n
+                self.display.scroll_down((instruction & 0x000F) as usize);
+                self.draw_flag = true;
+            },
+            0x00FB => { // SCR
+                self.display.scroll_right();
+                self.draw_flag = true;
+            },
+            0x00FC => { // SCL
+                self.display.scroll_left();
+                self.draw_flag = true;
+            },
+            0x00D0..=0x00DF if self.platform.has_xo_opcodes() => { // SCU n
+                self.display.scroll_up((instruction & 0x000F) as usize);
+                self.draw_flag = true;
+            },
+            0x00FD => { // EXIT
+                self.halt(HaltReason::Exit00FD);
+            },
+            0x00FE => { // LOW
+                self.display.set_high_resolution(false);
+                self.draw_flag = true;
+            },
+            0x00FF => { // HIGH
+                self.display.set_high_resolution(true);
+                self.draw_flag = true;
             },
             _ => {} // SYS ignored
         }
     }
     
     fn execute_1nnn(&mut self, instruction: u16) {
-        let addr = instruction & 0x0FFF;
-        
-        if !self.is_valid_program_address(addr) {
-            self.error_count += 1;
-            return;
+        if let Some(addr) = self.jump_target(instruction & 0x0FFF) {
+            self.pc = addr;
         }
-        
-        self.pc = addr;
     }
     
     fn execute_2nnn(&mut self, instruction: u16) {
-        let addr = instruction & 0x0FFF;
-        
-        if !self.is_valid_program_address(addr) {
-            self.error_count += 1;
+        let Some(addr) = self.jump_target(instruction & 0x0FFF) else {
             return;
-        }
+        };
         
-        if self.sp >= MAX_STACK_SIZE {
-            self.error_count += 1;
+        if self.sp as usize >= self.stack.len() {
+            self.report(CpuErrorKind::StackOverflow);
             return;
         }
         
@@ -242,13 +434,8 @@ impl Cpu {
         let x = ((instruction & 0x0F00) >> 8) as usize;
         let kk = (instruction & 0x00FF) as u8;
         
-        if x > 15 {
-            self.error_count += 1;
-            return;
-        }
-        
         if self.v[x] == kk {
-            self.pc += 2;
+            self.skip_next_instruction();
         }
     }
     
@@ -256,13 +443,8 @@ impl Cpu {
         let x = ((instruction & 0x0F00) >> 8) as usize;
         let kk = (instruction & 0x00FF) as u8;
         
-        if x > 15 {
-            self.error_count += 1;
-            return;
-        }
-        
         if self.v[x] != kk {
-            self.pc += 2;
+            self.skip_next_instruction();
         }
     }
     
@@ -270,13 +452,34 @@ impl Cpu {
         let x = ((instruction & 0x0F00) >> 8) as usize;
         let y = ((instruction & 0x00F0) >> 4) as usize;
         
-        if x > 15 || y > 15 {
-            self.error_count += 1;
-            return;
+        match instruction & 0x000F {
+            0x0 => { // SE Vx, Vy
+                if self.v[x] == self.v[y] {
+                    self.skip_next_instruction();
+                }
+            },
+            0x2 if self.platform.has_xo_opcodes() => { // SAVE Vx - Vy
+                for (offset, reg) in Self::register_range(x, y).enumerate() {
+                    self.store(self.i.wrapping_add(offset as u16), self.v[reg]);
+                }
+            },
+            0x3 if self.platform.has_xo_opcodes() => { // LOAD Vx - Vy
+                for (offset, reg) in Self::register_range(x, y).enumerate() {
+                    self.v[reg] = self.memory.read_byte(self.wrap(self.i.wrapping_add(offset as u16)));
+                }
+            },
+            _ => {
+                self.report(CpuErrorKind::UnknownOpcode);
+            }
         }
-        
-        if self.v[x] == self.v[y] {
-            self.pc += 2;
+    }
+    
+    /// Registres de x à y inclus, dans l'ordre décroissant si x > y (5xy2/5xy3)
+    fn register_range(x: usize, y: usize) -> Box<dyn Iterator<Item = usize>> {
+        if x <= y {
+            Box::new(x..=y)
+        } else {
+            Box::new((y..=x).rev())
         }
     }
     
@@ -284,11 +487,6 @@ impl Cpu {
         let x = ((instruction & 0x0F00) >> 8) as usize;
         let kk = (instruction & 0x00FF) as u8;
         
-        if x > 15 {
-            self.error_count += 1;
-            return;
-        }
-        
         self.v[x] = kk;
     }
     
@@ -296,11 +494,6 @@ impl Cpu {
         let x = ((instruction & 0x0F00) >> 8) as usize;
         let kk = (instruction & 0x00FF) as u8;
         
-        if x > 15 {
-            self.error_count += 1;
-            return;
-        }
-        
         self.v[x] = self.v[x].wrapping_add(kk);
     }
     
@@ -309,40 +502,54 @@ impl Cpu {
         let x = ((instruction & 0x0F00) >> 8) as usize;
         let y = ((instruction & 0x00F0) >> 4) as usize;
         
-        if x > 15 || y > 15 {
-            self.error_count += 1;
-            return;
-        }
-        
         match instruction & 0x000F {
             0x0 => self.v[x] = self.v[y], // LD Vx, Vy
-            0x1 => self.v[x] |= self.v[y], // OR Vx, Vy
-            0x2 => self.v[x] &= self.v[y], // AND Vx, Vy
-            0x3 => self.v[x] ^= self.v[y], // XOR Vx, Vy
+            0x1 => { // OR Vx, Vy
+                self.v[x] |= self.v[y];
+                if self.quirks.vf_reset {
+                    self.v[0xF] = 0;
+                }
+            },
+            0x2 => { // AND Vx, Vy
+                self.v[x] &= self.v[y];
+                if self.quirks.vf_reset {
+                    self.v[0xF] = 0;
+                }
+            },
+            0x3 => { // XOR Vx, Vy
+                self.v[x] ^= self.v[y];
+                if self.quirks.vf_reset {
+                    self.v[0xF] = 0;
+                }
+            },
+            // Le flag est écrit après le résultat : avec x = F, VF garde le flag
             0x4 => { // ADD Vx, Vy
-                let sum = self.v[x] as u16 + self.v[y] as u16;
-                self.v[0xF] = if sum > 255 { 1 } else { 0 }; // Carry flag
-                self.v[x] = sum as u8;
+                let (sum, carry) = self.v[x].overflowing_add(self.v[y]);
+                self.v[x] = sum;
+                self.v[0xF] = carry as u8; // Carry flag
             },
             0x5 => { // SUB Vx, Vy
-                self.v[0xF] = if self.v[x] >= self.v[y] { 1 } else { 0 }; // Not borrow flag
-                self.v[x] = self.v[x].wrapping_sub(self.v[y]);
+                let (difference, borrow) = self.v[x].overflowing_sub(self.v[y]);
+                self.v[x] = difference;
+                self.v[0xF] = !borrow as u8; // Not borrow flag
             },
-            0x6 => { // SHR Vx
-                self.v[0xF] = self.v[x] & 1; // LSB
-                self.v[x] >>= 1;
+            0x6 => { // SHR Vx {, Vy}
+                let source = if self.quirks.shift { self.v[x] } else { self.v[y] };
+                self.v[x] = source >> 1;
+                self.v[0xF] = source & 1; // LSB
             },
             0x7 => { // SUBN Vx, Vy
-                self.v[0xF] = if self.v[y] >= self.v[x] { 1 } else { 0 }; // Not borrow flag
-                self.v[x] = self.v[y].wrapping_sub(self.v[x]);
+                let (difference, borrow) = self.v[y].overflowing_sub(self.v[x]);
+                self.v[x] = difference;
+                self.v[0xF] = !borrow as u8; // Not borrow flag
             },
-            0xE => {  // SHL Vx
-                self.v[0xF] = (self.v[x] & 0x80) >> 7; // MSB
-                self.v[x] <<= 1;
+            0xE => {  // SHL Vx {, Vy}
+                let source = if self.quirks.shift { self.v[x] } else { self.v[y] };
+                self.v[x] = source << 1;
+                self.v[0xF] = (source & 0x80) >> 7; // MSB
             },
             _ => {
-                web_sys::console::log_1(&format!("Instruction 8xy{:X} inconnue", instruction & 0x000F).into());
-                self.error_count += 1;
+                self.report(CpuErrorKind::UnknownOpcode);
             }
         }
     }
@@ -352,13 +559,8 @@ impl Cpu {
         let x = ((instruction & 0x0F00) >> 8) as usize;
         let y = ((instruction & 0x00F0) >> 4) as usize;
         
-        if x > 15 || y > 15 {
-            self.error_count += 1;
-            return;
-        }
-        
         if self.v[x] != self.v[y] {
-            self.pc += 2; // Skip next instruction
+            self.skip_next_instruction();
         }
     }
     
@@ -367,28 +569,21 @@ impl Cpu {
         let nnn = instruction & 0x0FFF;
         
         // Permettre I de pointer vers toute la mémoire (y compris fonts)
-        if nnn >= MAX_MEMORY {
-            web_sys::console::log_1(&format!("I hors limites: 0x{:04X}", nnn).into());
-            self.error_count += 1;
+        if nnn as usize >= self.memory.size() {
+            self.report(CpuErrorKind::MemoryOutOfBounds { address: nnn, length: 1 });
             return;
         }
         
         self.i = nnn;
     }
     
-    /// Bnnn - JP V0, addr : PC = V0 + nnn
+    /// Bnnn - JP V0, addr : PC = V0 + nnn (Vx + nnn avec le quirk jump_vx)
     fn execute_bnnn(&mut self, instruction: u16) {
         let nnn = instruction & 0x0FFF;
-        let target = self.v[0] as u16 + nnn;
-        
-        if !self.is_valid_program_address(target) {
-            web_sys::console::log_1(&format!("Jump V0+nnn invalide: V0={:02X} + {:03X} = {:04X}", 
-                                            self.v[0], nnn, target).into());
-            self.error_count += 1;
-            return;
+        let reg = if self.quirks.jump_vx { ((instruction & 0x0F00) >> 8) as usize } else { 0 };
+        if let Some(target) = self.jump_target(self.v[reg] as u16 + nnn) {
+            self.pc = target;
         }
-        
-        self.pc = target;
     }
     
     /// Cxkk - RND Vx, byte : Vx = random & kk
@@ -396,18 +591,8 @@ impl Cpu {
         let x = ((instruction & 0x0F00) >> 8) as usize;
         let kk = (instruction & 0x00FF) as u8;
         
-        if x > 15 {
-            self.error_count += 1;
-            return;
-        }
-        
-        // Générateur aléatoire simple mais amélioré
-        static mut SEED: u32 = 12345;
-        unsafe {
-            SEED = SEED.wrapping_mul(1103515245).wrapping_add(12345);
-            let random = (SEED >> 16) as u8;
-            self.v[x] = random & kk;
-        }
+        let random = self.rng.next_byte(&self.memory);
+        self.v[x] = random & kk;
     }
     
     /// Dxyn - DRW Vx, Vy, nibble : Dessiner sprite
@@ -416,35 +601,49 @@ impl Cpu {
         let y = ((instruction & 0x00F0) >> 4) as usize;
         let n = (instruction & 0x000F) as u8;
         
-        if x > 15 || y > 15 {
-            self.error_count += 1;
+        if n == 0 && !self.platform.has_schip_opcodes() {
+            self.warn(CpuErrorKind::UnknownOpcode);
             return;
         }
         
-        if n == 0 {
-            web_sys::console::log_1(&"DRW avec hauteur 0, ignoré".into());
-            return;
+        // Attendre le prochain rafraîchissement si le quirk est actif
+        if self.quirks.display_wait {
+            if !self.vblank {
+                self.repeat_instruction();
+                return;
+            }
+            self.vblank = false;
         }
         
         // Position du sprite
         let pos_x = self.v[x] as usize;
         let pos_y = self.v[y] as usize;
         
-        // Vérifier que I + n ne dépasse pas la mémoire
-        if self.i as usize + n as usize > 4096 {
-            web_sys::console::log_1(&format!("DRW: I+n dépasse mémoire: I=0x{:04X}, n={}", self.i, n).into());
-            self.error_count += 1;
+        // Dxy0 en SCHIP : sprite 16x16 sur 32 bytes, répété pour chaque plan XO-CHIP
+        let plane_size = if n == 0 { 32 } else { n };
+        let size = plane_size * self.display.plane_count() as u8;
+        
+        // Vérifier que I + taille ne dépasse pas la mémoire
+        if !self.check_range(self.i, size as u16) {
             return;
         }
         
         // Lire les données du sprite depuis la mémoire
-        let sprite_data = self.memory.read_bytes(self.i, n);
+        let sprite_data = self.read_block(self.i, size as u16);
         
         // Dessiner et vérifier les collisions
-        let collision = self.display.draw_sprite(pos_x, pos_y, &sprite_data);
+        let collided_rows = if n == 0 {
+            self.display.draw_sprite_16(pos_x, pos_y, &sprite_data, self.quirks.clip)
+        } else {
+            self.display.draw_sprite(pos_x, pos_y, &sprite_data, self.quirks.clip)
+        };
         
-        // VF = flag de collision
-        self.v[0xF] = if collision { 1 } else { 0 };
+        // VF = flag de collision (nombre de lignes en collision en haute résolution SCHIP)
+        self.v[0xF] = if self.platform == Platform::SuperChip && self.display.is_high_resolution() {
+            collided_rows as u8
+        } else {
+            (collided_rows > 0) as u8
+        };
         
         // Marquer pour redessiner
         self.draw_flag = true;
@@ -454,32 +653,30 @@ impl Cpu {
     fn execute_exxx(&mut self, instruction: u16) {
         let x = ((instruction & 0x0F00) >> 8) as usize;
         
-        if x > 15 {
-            self.error_count += 1;
-            return;
-        }
-        
-        let key = self.v[x];
+        let mut key = self.v[x];
         
+        // Le COSMAC VIP ne lit que le quartet bas
         if key > 15 {
-            web_sys::console::log_1(&format!("Clé invalide: 0x{:02X}", key).into());
-            return;
+            if self.policy != ExecutionPolicy::Accurate {
+                self.warn(CpuErrorKind::InvalidKey { key });
+                return;
+            }
+            key &= 0x0F;
         }
         
         match instruction & 0x00FF {
             0x9E => { // SKP Vx : Skip si touche Vx pressée
                 if self.input.is_key_pressed(key) {
-                    self.pc += 2;
+                    self.skip_next_instruction();
                 }
             },
             0xA1 => { // SKNP Vx : Skip si touche Vx pas pressée
                 if !self.input.is_key_pressed(key) {
-                    self.pc += 2;
+                    self.skip_next_instruction();
                 }
             },
             _ => {
-                web_sys::console::log_1(&format!("Instruction Ex{:02X} inconnue", instruction & 0x00FF).into());
-                self.error_count += 1;
+                self.report(CpuErrorKind::UnknownOpcode);
             }
         }
     }
@@ -488,90 +685,181 @@ impl Cpu {
     fn execute_fxxx(&mut self, instruction: u16) {
         let x = ((instruction & 0x0F00) >> 8) as usize;
         
-        if x > 15 {
-            self.error_count += 1;
+        // F000 nnnn - LD I, long addr : I = mot de 16 bits suivant (XO-CHIP)
+        if instruction == 0xF000 && self.platform.has_xo_opcodes() {
+            self.i = self.read_word(self.pc);
+            self.pc = self.pc.wrapping_add(2);
             return;
         }
         
         match instruction & 0x00FF {
+            0x01 if self.platform.has_xo_opcodes() => { // PLANE n
+                self.display.select_planes(x as u8);
+            },
+            0x02 if x == 0 && self.platform.has_xo_opcodes() => { // AUDIO
+                if !self.check_range(self.i, 16) {
+                    return;
+                }
+                let pattern = self.read_block(self.i, 16);
+                self.audio.load_pattern(&pattern);
+            },
             0x07 => self.v[x] = self.delay_timer, // LD Vx, DT
-            0x0A => { // LD Vx, K (attendre touche)
-                if let Some(key) = self.input.get_key_pressed() {
+            0x0A => { // LD Vx, K (attendre l'appui puis le relâchement d'une touche)
+                self.input.wait_for_key();
+                if let Some(key) = self.input.take_key_event(self.quirks.key_press) {
                     self.v[x] = key;
+                    self.input.stop_waiting();
                 } else {
-                    self.pc -= 2; // Répéter l'instruction jusqu'à avoir une touche
+                    self.repeat_instruction(); // Les timers continuent
                 }
             },
             0x15 => self.delay_timer = self.v[x], // LD DT, Vx
             0x18 => self.sound_timer = self.v[x], // LD ST, Vx
             0x1E => { // ADD I, Vx
                 let new_i = self.i.wrapping_add(self.v[x] as u16);
-                if new_i >= MAX_MEMORY {
-                    web_sys::console::log_1(&format!("ADD I,Vx dépasse: I=0x{:04X}+{:02X}=0x{:04X}", 
-                                                    self.i, self.v[x], new_i).into());
+                if new_i as usize >= self.memory.size() {
+                    self.warn(CpuErrorKind::MemoryOutOfBounds { address: self.i, length: self.v[x] as u16 + 1 });
                 }
-                self.i = new_i & 0x0FFF; // Maintenir dans les limites
+                self.i = self.wrap(new_i); // Maintenir dans les limites
             },
             0x29 => { // LD F, Vx
                 let character = self.v[x] & 0x0F; // Seulement 0-F
                 self.i = self.memory.get_font_address(character);
             },
+            0x30 if self.platform.has_schip_opcodes() => { // LD HF, Vx
+                let character = self.v[x] & 0x0F;
+                self.i = self.memory.get_big_font_address(character);
+            },
+            0x3A if self.platform.has_xo_opcodes() => { // PITCH Vx
+                self.audio.set_pitch(self.v[x]);
+            },
             0x33 => { // LD B, Vx (BCD)
                 let value = self.v[x];
-                if self.i + 2 >= MAX_MEMORY {
-                    web_sys::console::log_1(&"BCD: pas assez de place en mémoire".into());
-                    self.error_count += 1;
+                if !self.check_range(self.i, 3) {
                     return;
                 }
-                self.memory.write_byte(self.i, value / 100); // Centaines
-                self.memory.write_byte(self.i + 1, (value / 10) % 10); // Dizaines
-                self.memory.write_byte(self.i + 2, value % 10); // Unités
+                self.store(self.i, value / 100); // Centaines
+                self.store(self.i.wrapping_add(1), (value / 10) % 10); // Dizaines
+                self.store(self.i.wrapping_add(2), value % 10); // Unités
             },
             0x55 => { // LD [I], Vx
-                if self.i as usize + x >= 4096 {
-                    web_sys::console::log_1(&"Store: pas assez de place".into());
-                    self.error_count += 1;
+                if !self.check_range(self.i, x as u16 + 1) {
                     return;
                 }
                 for reg in 0..=x {
-                    self.memory.write_byte(self.i + reg as u16, self.v[reg]);
+                    self.store(self.i.wrapping_add(reg as u16), self.v[reg]);
+                }
+                if self.quirks.memory_increment {
+                    self.i = self.i.wrapping_add(x as u16 + 1);
                 }
             },
             0x65 => { // LD Vx, [I]
-                if self.i as usize + x >= 4096 {
-                    web_sys::console::log_1(&"Load: pas assez de mémoire".into());
-                    self.error_count += 1;
+                if !self.check_range(self.i, x as u16 + 1) {
                     return;
                 }
                 for reg in 0..=x {
-                    self.v[reg] = self.memory.read_byte(self.i + reg as u16);
+                    self.v[reg] = self.memory.read_byte(self.wrap(self.i.wrapping_add(reg as u16)));
+                }
+                if self.quirks.memory_increment {
+                    self.i = self.i.wrapping_add(x as u16 + 1);
+                }
+            },
+            0x75 if self.platform.has_schip_opcodes() => { // LD R, Vx
+                if x > 7 && !self.platform.has_xo_opcodes() {
+                    self.report(CpuErrorKind::InvalidRegister { register: x as u8 });
+                    return;
+                }
+                self.rpl[..=x].copy_from_slice(&self.v[..=x]);
+            },
+            0x85 if self.platform.has_schip_opcodes() => { // LD Vx, R
+                if x > 7 && !self.platform.has_xo_opcodes() {
+                    self.report(CpuErrorKind::InvalidRegister { register: x as u8 });
+                    return;
                 }
+                self.v[..=x].copy_from_slice(&self.rpl[..=x]);
             },
             _ => {
-                web_sys::console::log_1(&format!("Instruction Fx{:02X} inconnue", instruction & 0x00FF).into());
-                self.error_count += 1;
+                self.report(CpuErrorKind::UnknownOpcode);
             }
         }
     }
     
     // ========== FONCTIONS UTILITAIRES ==========
     
+    /// Anomalie tolérée : seulement journalisée, sauf en politique stricte
+    fn warn(&mut self, kind: CpuErrorKind) {
+        if self.policy == ExecutionPolicy::Strict {
+            self.report(kind);
+            return;
+        }
+        let error = CpuError { pc: self.instruction_address, opcode: self.instruction, kind };
+        log(&error.describe(&self.symbols));
+    }
+    
+    /// Enregistrer une erreur de l'instruction en cours ; le CPU s'arrête au-delà
+    /// de MAX_ERRORS, ou dès la première en politique stricte
+    fn report(&mut self, kind: CpuErrorKind) {
+        let error = CpuError { pc: self.instruction_address, opcode: self.instruction, kind };
+        log(&error.describe(&self.symbols));
+        
+        if self.errors.len() == ERROR_LOG_SIZE {
+            self.errors.pop_front();
+        }
+        self.errors.push_back(error);
+        self.error_count += 1;
+        
+        if self.policy == ExecutionPolicy::Strict {
+            self.halt(HaltReason::Trap);
+        } else if self.error_limit_reached() {
+            self.halt(HaltReason::ErrorLimit);
+            if !self.tracer.is_empty() {
+                log(&format!("CPU arrêté après {} erreurs, dernières instructions:\n{}", self.error_count, self.trace_text(8).trim_end()));
+            }
+        }
+    }
+    
+    /// Arrêter le CPU ; la première raison est conservée
+    fn halt(&mut self, reason: HaltReason) {
+        if self.halt_reason.is_none() {
+            self.halt_reason = Some(reason);
+        }
+    }
+    
+    pub fn is_halted(&self) -> bool {
+        self.halt_reason.is_some()
+    }
+    
+    /// Dernières erreurs d'exécution, de la plus ancienne à la plus récente
+    pub fn errors(&self) -> impl DoubleEndedIterator<Item = &CpuError> + ExactSizeIterator {
+        self.errors.iter()
+    }
+    
+    /// Vider l'historique des erreurs (le compteur est conservé)
+    pub fn clear_errors(&mut self) {
+        self.errors.clear();
+    }
+    
     /// Vérifier qu'une adresse est valide pour un programme
     fn is_valid_program_address(&self, addr: u16) -> bool {
-        addr >= PROGRAM_START && addr < MAX_MEMORY && addr % 2 == 0
+        addr >= PROGRAM_START && (addr as usize) < self.memory.size() && addr.is_multiple_of(2)
     }
     
     /// Obtenir les statistiques du CPU
     pub fn get_stats(&self) -> String {
         format!(
             "Cycles: {} | Erreurs: {} | Halted: {} | Stack: {}/{}",
-            self.cycle_count, self.error_count, self.halted, self.sp, MAX_STACK_SIZE
+            self.cycle_count, self.error_count, self.is_halted(), self.sp, self.stack.len()
         )
     }
     
+    /// Le CPU a-t-il accumulé trop d'erreurs pour continuer ?
+    pub fn error_limit_reached(&self) -> bool {
+        self.error_count > MAX_ERRORS
+    }
+    
     /// Vérifier l'état de santé du CPU
     pub fn is_healthy(&self) -> bool {
-        !self.halted && self.error_count < 5 && self.sp < MAX_STACK_SIZE
+        !self.is_halted() && self.error_count < 5 && (self.sp as usize) < self.stack.len()
     }
     
     //  FONCTIONS POUR JAVASCRIPT
@@ -581,6 +869,63 @@ impl Cpu {
         self.display.get_buffer()
     }
     
+    /// Buffer des index de couleur 0-3 (bitplanes XO-CHIP)
+    pub fn get_plane_buffer(&self) -> Vec<u8> {
+        self.display.get_plane_buffer()
+    }
+    
+    pub fn policy(&self) -> ExecutionPolicy {
+        self.policy
+    }
+    
+    /// Changer de politique d'exécution, la protection des fonts suit la politique
+    pub fn set_policy(&mut self, policy: ExecutionPolicy) {
+        self.policy = policy;
+        self.memory.set_write_protection(policy.protects_fonts());
+    }
+    
+    pub fn stack_depth(&self) -> usize {
+        self.stack.len()
+    }
+    
+    /// Changer la profondeur de pile, false si elle est hors 1-255 ou
+    /// plus petite que le nombre d'appels en cours
+    pub fn set_stack_depth(&mut self, depth: usize) -> bool {
+        if depth == 0 || depth > MAX_STACK_DEPTH || depth < self.sp as usize {
+            return false;
+        }
+        self.stack.resize(depth, 0);
+        true
+    }
+    
+    /// Comme `set_stack_depth`, mais les appels qui ne tiennent plus sont oubliés
+    pub(crate) fn resize_stack(&mut self, depth: usize) {
+        self.stack.resize(depth, 0);
+        self.sp = self.sp.min(depth as u8);
+    }
+    
+    /// Adresses de retour empilées, de la plus ancienne à la plus récente
+    pub fn call_stack(&self) -> &[u16] {
+        &self.stack[..self.sp as usize]
+    }
+    
+    /// Changer de plateforme, les quirks recommandés sont appliqués
+    pub fn set_platform(&mut self, platform: Platform) {
+        self.platform = platform;
+        self.quirks = platform.default_quirks();
+        
+        if !platform.has_schip_opcodes() {
+            self.display.set_high_resolution(false);
+        }
+        
+        self.resize_stack(platform.stack_depth());
+        
+        if !platform.has_xo_opcodes() {
+            self.display.select_planes(1);
+        }
+        self.memory.resize(platform.memory_size());
+    }
+    
     /// Gérer les touches
     pub fn key_down(&mut self, key: u8) {
         if key <= 15 {
@@ -595,19 +940,189 @@ impl Cpu {
     }
     
     /// Informations de debug complètes
+    /// PC et I sont affichés en symbolique (`main+0x12 (game.8o:42)`) si des symboles sont chargés
     pub fn get_debug_info(&self) -> String {
         format!(
-            "PC: 0x{:04X} | I: 0x{:04X} | SP: {} | DT: {} | ST: {} | V0-F: {:02X?} | Cycles: {} | Err: {}",
-            self.pc, self.i, self.sp, self.delay_timer, self.sound_timer, self.v, self.cycle_count, self.error_count
+            "PC: {} | I: {} | SP: {} | DT: {} | ST: {} | V0-F: {:02X?} | Cycles: {} | Err: {}",
+            self.symbols.describe(self.pc), self.symbols.describe(self.i), self.sp,
+            self.delay_timer, self.sound_timer, self.v, self.cycle_count, self.error_count
         )
     }
     
     /// État simple pour l'interface
     pub fn is_running(&self) -> bool {
-        !self.halted && self.is_healthy()
+        !self.is_halted() && self.is_healthy()
     }
     
+    /// Dump hexadécimal suivi des labels de la zone
     pub fn memory_dump(&self, start: u16, length: u16) -> String {
-        self.memory.hex_dump(start, length)
+        let mut dump = self.memory.hex_dump(start, length);
+        let end = start as u32 + length as u32;
+        let labels: Vec<u16> = (start as u32..end)
+            .map(|address| address as u16)
+            .filter(|&address| self.symbols.label_at(address).is_some())
+            .collect();
+        
+        if !labels.is_empty() {
+            dump.push_str(" Labels:\n");
+            for address in labels {
+                dump.push_str(&format!("{:04X}: {}\n", address, self.symbols.describe(address)));
+            }
+        }
+        dump
+    }
+    
+    /// Les `last` dernières entrées de la trace (toutes si 0), désassemblées en commentaire
+    pub fn trace_text(&self, last: usize) -> String {
+        let skip = if last == 0 { 0 } else { self.tracer.len().saturating_sub(last) };
+        let mut text = String::new();
+        for entry in self.tracer.entries().skip(skip) {
+            let instruction = disasm::disassemble(entry.opcode, self.platform, Syntax::Classic);
+            text.push_str(&format!("{:<40} ; {}", entry.to_text(), instruction));
+            if !self.symbols.is_empty() {
+                text.push_str(&format!("  {}", self.symbols.describe(entry.pc)));
+            }
+            text.push('\n');
+        }
+        text
+    }
+    
+    /// Pile d'appels comme une backtrace : `#0` est le retour le plus proche
+    pub fn call_stack_text(&self) -> String {
+        self.call_stack().iter().rev().enumerate()
+            .map(|(level, &address)| format!("#{} {}\n", level, self.symbols.describe(address)))
+            .collect()
+    }
+    
+    /// Listing désassemblé, la ligne du PC est marquée par `>`
+    pub fn disassemble(&self, start: u16, count: usize, syntax: Syntax) -> String {
+        let mut listing = String::new();
+        for line in disasm::disassemble_range(&self.memory, start, count, self.platform, syntax) {
+            let marker = if line.address == self.pc { '>' } else { ' ' };
+            let bytes = match line.operand {
+                Some(operand) => format!("{:04X} {:04X}", line.opcode, operand),
+                None => format!("{:04X}     ", line.opcode),
+            };
+            if let Some(label) = self.symbols.label_at(line.address) {
+                listing.push_str(&format!("{}:\n", label));
+            }
+            listing.push_str(&format!("{} {:04X}: {}  {}", marker, line.address, bytes, line.text));
+            if let Some(source_line) = self.symbols.line_at(line.address) {
+                listing.push_str(&format!("  ; {}:{}", self.symbols.file(), source_line));
+            }
+            listing.push('\n');
+        }
+        listing
     }
-}
\ No newline at end of file
+}
+
+impl Cpu {
+    /// Photographier toute la machine (registres, pile, timers, RAM, écran, clavier)
+    pub fn save_state(&self) -> Vec<u8> {
+        savestate::encode(&self.snapshot())
+    }
+    
+    /// Restaurer une sauvegarde ; en cas d'erreur l'état courant est conservé
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), String> {
+        let payload = savestate::decode(data)?;
+        self.restore_snapshot(payload)?;
+        
+        log("État restauré");
+        Ok(())
+    }
+    
+    /// État brut, sans en-tête ni somme de contrôle (historique de rewind)
+    pub fn snapshot(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        self.write_state(&mut writer);
+        writer.finish()
+    }
+    
+    /// Relire un état brut produit par `snapshot`, tout ou rien
+    pub fn restore_snapshot(&mut self, payload: &[u8]) -> Result<(), String> {
+        let backup = self.snapshot();
+        
+        let mut reader = StateReader::new(payload);
+        let result = self.read_state(&mut reader).and_then(|_| reader.finish());
+        
+        match result {
+            // Les instructions annulées par un retour en arrière sortent de la trace
+            Ok(()) => self.tracer.discard_after(self.cycle_count),
+            Err(_) => self.read_state(&mut StateReader::new(&backup))
+                .expect("l'état courant doit toujours pouvoir être relu"),
+        }
+        result
+    }
+    
+    fn write_state(&self, writer: &mut StateWriter) {
+        writer.u8(self.platform.id());
+        self.quirks.save_state(writer);
+        
+        writer.bytes(&self.v);
+        writer.u16(self.i);
+        writer.u16(self.pc);
+        writer.u8(self.sp);
+        writer.u8(self.delay_timer);
+        writer.u8(self.sound_timer);
+        writer.u8(self.stack.len() as u8);
+        for &address in &self.stack {
+            writer.u16(address);
+        }
+        writer.bytes(&self.rpl);
+        self.rng.save_state(writer);
+        
+        writer.bool(self.draw_flag);
+        writer.bool(self.vblank);
+        writer.u8(self.halt_reason.map_or(0, |reason| reason.id() + 1));
+        writer.u32(self.error_count);
+        writer.u64(self.cycle_count);
+        
+        self.memory.save_state(writer);
+        self.display.save_state(writer);
+        self.input.save_state(writer);
+        self.audio.save_state(writer);
+    }
+    
+    fn read_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        let platform = reader.u8()?;
+        self.platform = Platform::from_id(platform)
+            .ok_or_else(|| format!("Plateforme inconnue dans la sauvegarde: {}", platform))?;
+        self.quirks.load_state(reader)?;
+        
+        reader.bytes(&mut self.v)?;
+        self.i = reader.u16()?;
+        self.pc = reader.u16()?;
+        self.sp = reader.u8()?;
+        self.delay_timer = reader.u8()?;
+        self.sound_timer = reader.u8()?;
+        let depth = reader.u8()? as usize;
+        if depth == 0 || self.sp as usize > depth {
+            return Err(format!("Pile invalide: SP {} pour {} niveaux", self.sp, depth));
+        }
+        self.stack.resize(depth, 0);
+        for address in self.stack.iter_mut() {
+            *address = reader.u16()?;
+        }
+        reader.bytes(&mut self.rpl)?;
+        self.rng.load_state(reader)?;
+        
+        self.draw_flag = reader.bool()?;
+        self.vblank = reader.bool()?;
+        self.halt_reason = match reader.u8()? {
+            0 => None,
+            id => Some(HaltReason::from_id(id - 1)
+                .ok_or_else(|| format!("Raison d'arrêt inconnue dans la sauvegarde: {}", id))?),
+        };
+        self.error_count = reader.u32()?;
+        self.cycle_count = reader.u64()?;
+        
+        self.memory.load_state(reader)?;
+        self.display.load_state(reader)?;
+        self.input.load_state(reader)?;
+        self.audio.load_state(reader)?;
+        
+        // L'écran doit être redessiné avec le contenu restauré
+        self.draw_flag = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+//! Base de données des ROMs au format de chip-8-database (`programs.json`)
+//! Chaque ROM est identifiée par le SHA-1 de son fichier ; son entrée donne la
+//! plateforme, la vitesse, les quirks, les contrôles et les couleurs à utiliser.
+//! Une base réduite est intégrée au binaire, le fichier de la communauté peut
+//! être chargé par-dessus
+
+use crate::gamepad::{GamepadInput, GamepadMap};
+use crate::json::JsonValue;
+use crate::sha1::sha1_hex;
+use crate::{KeyMap, Platform, Quirks};
+
+/// Base intégrée : Pong et les ROMs de `roms to test/` vérifiées sans interface
+/// (plateforme seulement, vitesse et quirks par défaut de la plateforme)
+const BUNDLED: &str = include_str!("database.json");
+
+/// Plateformes de chip-8-database, par ordre de préférence : équivalent
+/// Ferris-8, quirks et instructions par frame de référence
+/// (chip8x et megachip8 ne sont pas émulées)
+const PLATFORMS: [(&str, Platform, Quirks, u32); 7] = [
+    ("originalChip8", Platform::Chip8, Quirks::vip(), 15),
+    ("hybridVIP", Platform::Chip8, Quirks::vip(), 15),
+    ("modernChip8", Platform::Chip8, Quirks { vf_reset: false, display_wait: false, ..Quirks::vip() }, 12),
+    ("chip48", Platform::Chip8, Quirks { memory_increment: true, ..Quirks::schip() }, 30),
+    ("superchip1", Platform::SuperChip, Quirks::schip(), 30),
+    ("superchip", Platform::SuperChip, Quirks::schip(), 30),
+    ("xochip", Platform::XoChip, Quirks::xo_chip(), 100),
+];
+
+/// Contrôles nommés de chip-8-database : touches du clavier et entrées de
+/// manette qui les déclenchent
+const CONTROLS: [(&str, &[&str], &[&str]); 12] = [
+    ("up", &["ArrowUp"], &["up", "ly-"]),
+    ("down", &["ArrowDown"], &["down", "ly+"]),
+    ("left", &["ArrowLeft"], &["left", "lx-"]),
+    ("right", &["ArrowRight"], &["right", "lx+"]),
+    ("a", &["Space"], &["a"]),
+    ("b", &["Enter"], &["b"]),
+    ("player2Up", &[], &["ry-"]),
+    ("player2Down", &[], &["ry+"]),
+    ("player2Left", &[], &["rx-"]),
+    ("player2Right", &[], &["rx+"]),
+    ("player2A", &[], &["rb"]),
+    ("player2B", &[], &["lb"]),
+];
+
+/// Réglages conseillés pour une ROM
+#[derive(Clone, Debug, PartialEq)]
+pub struct RomInfo {
+    /// SHA-1 du fichier en hexadécimal minuscule
+    pub sha1: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub platform: Platform,
+    pub quirks: Quirks,
+    /// Instructions par frame ("tickrate")
+    pub cycles_per_frame: u32,
+    /// Contrôles nommés ("up", "a", "player2Up"...) et leur touche Chip-8
+    pub controls: Vec<(String, u8)>,
+    /// Couleurs des index de plans 0-3 en 0xRRGGBB, vide si la ROM n'en impose pas
+    pub palette: Vec<u32>,
+}
+
+impl RomInfo {
+    /// `base` avec les flèches, Espace et Entrée sur les contrôles de la ROM
+    pub fn key_map(&self, base: &KeyMap) -> KeyMap {
+        let mut map = base.clone();
+        for (control, codes, _) in CONTROLS {
+            for code in codes {
+                map.unbind(code);
+                if let Some(key) = self.control_key(control) {
+                    map.bind(code, key);
+                }
+            }
+        }
+        map
+    }
+    
+    /// Manette réduite aux contrôles de la ROM (croix et stick gauche, stick droit
+    /// pour le joueur 2), None si la ROM n'en déclare pas
+    pub fn gamepad_map(&self, dead_zone: f32) -> Option<GamepadMap> {
+        if self.controls.is_empty() {
+            return None;
+        }
+        let mut map = GamepadMap::empty();
+        map.set_dead_zone(dead_zone);
+        for (control, _, inputs) in CONTROLS {
+            if let Some(key) = self.control_key(control) {
+                for input in inputs.iter().filter_map(|name| GamepadInput::from_name(name)) {
+                    map.bind(input, key);
+                }
+            }
+        }
+        Some(map)
+    }
+    
+    fn control_key(&self, control: &str) -> Option<u8> {
+        self.controls.iter().find(|(name, _)| name == control).map(|&(_, key)| key)
+    }
+}
+
+/// ROMs connues, indexées par SHA-1
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RomDatabase {
+    roms: Vec<RomInfo>,
+}
+
+impl RomDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Base intégrée au binaire
+    pub fn bundled() -> Self {
+        Self::from_json(BUNDLED).expect("la base de ROMs intégrée doit être valide")
+    }
+    
+    /// Lire un `programs.json` de chip-8-database : un tableau de programmes,
+    /// chacun avec ses ROMs indexées par SHA-1. Les ROMs qui ne tournent que
+    /// sur des plateformes non émulées sont ignorées
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let JsonValue::Array(programs) = JsonValue::parse(text)? else {
+            return Err(String::from("La base de ROMs doit être un tableau de programmes"));
+        };
+        
+        let mut roms = Vec::new();
+        for (index, program) in programs.iter().enumerate() {
+            let title = program.get("title").and_then(JsonValue::as_str)
+                .ok_or_else(|| format!("Programme {}: \"title\" manquant", index))?;
+            let authors: Vec<String> = match program.get("authors") {
+                Some(JsonValue::Array(names)) => names.iter().filter_map(JsonValue::as_str).map(String::from).collect(),
+                _ => Vec::new(),
+            };
+            let entries = program.get("roms").and_then(JsonValue::as_object)
+                .ok_or_else(|| format!("{}: \"roms\" doit être un objet", title))?;
+            
+            for (sha1, rom) in entries {
+                if let Some(info) = parse_rom(sha1, rom, title, &authors).map_err(|e| format!("{}: {}", title, e))? {
+                    roms.push(info);
+                }
+            }
+        }
+        Ok(RomDatabase { roms })
+    }
+    
+    pub fn len(&self) -> usize {
+        self.roms.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.roms.is_empty()
+    }
+    
+    /// Ajouter les ROMs d'une autre base, qui remplacent celles de même SHA-1
+    pub fn merge(&mut self, other: RomDatabase) {
+        self.roms.retain(|rom| !other.roms.iter().any(|added| added.sha1 == rom.sha1));
+        self.roms.extend(other.roms);
+    }
+    
+    pub fn find(&self, sha1: &str) -> Option<&RomInfo> {
+        self.roms.iter().find(|rom| rom.sha1.eq_ignore_ascii_case(sha1))
+    }
+    
+    /// Réglages d'une ROM d'après le SHA-1 de son contenu
+    pub fn lookup(&self, rom: &[u8]) -> Option<&RomInfo> {
+        self.find(&sha1_hex(rom))
+    }
+}
+
+/// Une entrée de `roms`, None si aucune de ses plateformes n'est émulée
+fn parse_rom(sha1: &str, rom: &JsonValue, title: &str, authors: &[String]) -> Result<Option<RomInfo>, String> {
+    if sha1.len() != 40 || !sha1.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("SHA-1 invalide \"{}\"", sha1));
+    }
+    let Some(JsonValue::Array(platforms)) = rom.get("platforms") else {
+        return Err(format!("{}: \"platforms\" doit être un tableau", sha1));
+    };
+    // Première plateforme émulée dans l'ordre de la ROM (la préférée en tête)
+    let Some(&(platform_id, platform, mut quirks, default_rate)) = platforms.iter()
+        .filter_map(JsonValue::as_str)
+        .find_map(|id| PLATFORMS.iter().find(|(known, ..)| *known == id))
+    else {
+        return Ok(None);
+    };
+    
+    // Quirks propres à la ROM sur cette plateforme
+    if let Some(overrides) = rom.get("quirkyPlatforms").and_then(|quirky| quirky.get(platform_id)).and_then(JsonValue::as_object) {
+        for (name, value) in overrides {
+            let JsonValue::Bool(enabled) = *value else {
+                return Err(format!("{}: quirk \"{}\" doit être un booléen", sha1, name));
+            };
+            apply_quirk(&mut quirks, name, enabled);
+        }
+    }
+    
+    let cycles_per_frame = match rom.get("tickrate") {
+        Some(rate) => rate.as_f64()
+            .filter(|&rate| rate >= 1.0 && rate <= u32::MAX as f64)
+            .ok_or_else(|| format!("{}: \"tickrate\" invalide", sha1))? as u32,
+        None => default_rate,
+    };
+    
+    let mut controls = Vec::new();
+    if let Some(keys) = rom.get("keys").and_then(JsonValue::as_object) {
+        for (control, key) in keys {
+            let key = key.as_f64()
+                .filter(|&key| (0.0..=15.0).contains(&key) && key.fract() == 0.0)
+                .ok_or_else(|| format!("{}: touche invalide pour \"{}\"", sha1, control))?;
+            controls.push((control.clone(), key as u8));
+        }
+    }
+    
+    let mut palette = Vec::new();
+    if let Some(JsonValue::Array(pixels)) = rom.get("colors").and_then(|colors| colors.get("pixels")) {
+        for color in pixels {
+            palette.push(color.as_str().and_then(parse_color)
+                .ok_or_else(|| format!("{}: couleur invalide {}", sha1, color))?);
+        }
+    }
+    
+    Ok(Some(RomInfo {
+        sha1: sha1.to_ascii_lowercase(),
+        title: String::from(title),
+        authors: authors.to_vec(),
+        platform,
+        quirks,
+        cycles_per_frame,
+        controls,
+        palette,
+    }))
+}
+
+/// Quirks de chip-8-database ; les noms inconnus sont ignorés
+fn apply_quirk(quirks: &mut Quirks, name: &str, enabled: bool) {
+    match name {
+        "shift" => quirks.shift = enabled,
+        // I avance de x (CHIP-48) : le plus proche est l'incrément du VIP
+        "memoryIncrementByX" if enabled => quirks.memory_increment = true,
+        "memoryLeaveIUnchanged" => quirks.memory_increment = !enabled,
+        "wrap" => quirks.clip = !enabled,
+        "jump" => quirks.jump_vx = enabled,
+        "vblank" => quirks.display_wait = enabled,
+        "logic" => quirks.vf_reset = enabled,
+        _ => {},
+    }
+}
+
+/// "#RRGGBB" -> 0xRRGGBB
+fn parse_color(text: &str) -> Option<u32> {
+    let hex = text.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
@@ -0,0 +1,343 @@
+//! Debugger : breakpoints (éventuellement conditionnels), pas à pas,
+//! step over / step out et exécution jusqu'au prochain arrêt
+//! Les watchpoints mémoire vivent dans `Memory`, au plus près des accès
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::Cpu;
+
+/// Raison de l'arrêt de l'exécution
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakKind {
+    /// Pas à pas, step over ou step out terminé
+    Step,
+    /// La frame demandée a été exécutée sans arrêt
+    FrameComplete,
+    Breakpoint,
+    WatchRead,
+    WatchWrite,
+    /// Le CPU s'est arrêté (00FD, pile vide...)
+    Halted,
+    /// Trop d'erreurs CPU
+    ErrorLimit,
+    /// Le nombre maximal d'instructions a été atteint
+    InstructionLimit,
+}
+
+/// Compte rendu d'une exécution sous le debugger
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BreakEvent {
+    pub kind: BreakKind,
+    /// PC au moment de l'arrêt
+    pub pc: u16,
+    /// Adresse du breakpoint ou de l'accès mémoire surveillé
+    pub address: u16,
+    /// Valeur lue ou écrite (watchpoints)
+    pub value: u8,
+    /// Instructions exécutées avant l'arrêt
+    pub instructions: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl BreakEvent {
+    /// Description lisible pour le panneau du debugger
+    pub fn describe(&self) -> String {
+        match self.kind {
+            BreakKind::Step => format!("Pas terminé à 0x{:04X}", self.pc),
+            BreakKind::FrameComplete => format!("Frame terminée ({} instructions)", self.instructions),
+            BreakKind::Breakpoint => format!("Breakpoint à 0x{:04X}", self.address),
+            BreakKind::WatchRead => format!("Lecture de 0x{:04X} = 0x{:02X} (PC 0x{:04X})", self.address, self.value, self.pc),
+            BreakKind::WatchWrite => format!("Écriture de 0x{:02X} en 0x{:04X} (PC 0x{:04X})", self.value, self.address, self.pc),
+            BreakKind::Halted => format!("CPU arrêté à 0x{:04X}", self.pc),
+            BreakKind::ErrorLimit => format!("Trop d'erreurs CPU (PC 0x{:04X})", self.pc),
+            BreakKind::InstructionLimit => format!("Limite de {} instructions atteinte", self.instructions),
+        }
+    }
+}
+
+/// Registre testé par un breakpoint conditionnel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Register {
+    V(usize),
+    I,
+    DelayTimer,
+    SoundTimer,
+}
+
+impl Register {
+    /// "V0".."VF", "I", "DT", "ST"
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_uppercase();
+        match name.as_str() {
+            "I" => Some(Register::I),
+            "DT" => Some(Register::DelayTimer),
+            "ST" => Some(Register::SoundTimer),
+            _ => {
+                let index = name.strip_prefix('V')?;
+                let index = usize::from_str_radix(index, 16).ok()?;
+                (index < 16 && name.len() == 2).then_some(Register::V(index))
+            },
+        }
+    }
+    
+    fn read(self, cpu: &Cpu) -> u16 {
+        match self {
+            Register::V(index) => cpu.v[index] as u16,
+            Register::I => cpu.i,
+            Register::DelayTimer => cpu.delay_timer as u16,
+            Register::SoundTimer => cpu.sound_timer as u16,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "==" | "=" => Some(Comparison::Equal),
+            "!=" => Some(Comparison::NotEqual),
+            "<" => Some(Comparison::Less),
+            "<=" => Some(Comparison::LessOrEqual),
+            ">" => Some(Comparison::Greater),
+            ">=" => Some(Comparison::GreaterOrEqual),
+            _ => None,
+        }
+    }
+    
+    fn test(self, left: u16, right: u16) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Condition {
+    pub register: Register,
+    pub comparison: Comparison,
+    pub value: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub address: u16,
+    pub condition: Option<Condition>,
+}
+
+impl Breakpoint {
+    fn hit(&self, cpu: &Cpu) -> bool {
+        cpu.pc == self.address
+            && self.condition.is_none_or(|c| c.comparison.test(c.register.read(cpu), c.value))
+    }
+}
+
+/// Critère d'arrêt en plus des breakpoints
+#[derive(Clone, Copy)]
+enum Until {
+    /// S'arrêter à la fin de la frame en cours
+    FrameEnd,
+    /// Revenir à `pc` avec la même profondeur de pile (step over)
+    Return { pc: u16, sp: u8 },
+    /// Dépiler sous la profondeur de départ (step out)
+    StackBelow(u8),
+    /// Breakpoints, watchpoints et arrêts du CPU ; en pas à pas, la limite d'instructions
+    Break,
+}
+
+pub struct Debugger {
+    breakpoints: Vec<Breakpoint>,
+    /// Instructions par frame, pour faire tourner les timers pendant le pas à pas
+    cycles_per_frame: u32,
+    /// Instructions déjà exécutées dans la frame en cours
+    frame_cycles: u32,
+    /// PC du dernier arrêt : on ne s'y arrête pas une seconde fois en reprenant
+    resume_pc: Option<u16>,
+    last_break: Option<BreakEvent>,
+}
+
+impl Default for Debugger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debugger {
+    pub fn new() -> Self {
+        Debugger {
+            breakpoints: Vec::new(),
+            cycles_per_frame: 10,
+            frame_cycles: 0,
+            resume_pc: None,
+            last_break: None,
+        }
+    }
+    
+    /// Oublier l'exécution en cours (reset, chargement de ROM ou d'état)
+    pub fn reset(&mut self) {
+        self.frame_cycles = 0;
+        self.resume_pc = None;
+        self.last_break = None;
+    }
+    
+    pub fn add_breakpoint(&mut self, address: u16, condition: Option<Condition>) {
+        self.breakpoints.push(Breakpoint { address, condition });
+    }
+    
+    /// Retirer tous les breakpoints posés à cette adresse
+    pub fn remove_breakpoint(&mut self, address: u16) -> bool {
+        let before = self.breakpoints.len();
+        self.breakpoints.retain(|breakpoint| breakpoint.address != address);
+        self.breakpoints.len() != before
+    }
+    
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+    
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+    
+    /// Faut-il passer par la boucle instrumentée ?
+    pub fn is_active(&self, cpu: &Cpu) -> bool {
+        !self.breakpoints.is_empty() || !cpu.memory.watchpoints().is_empty()
+    }
+    
+    pub fn last_break(&self) -> Option<BreakEvent> {
+        self.last_break
+    }
+    
+    /// Une frame complète, interrompue par un breakpoint ou un watchpoint
+    pub fn run_frame(&mut self, cpu: &mut Cpu, cycles_per_frame: u32) -> BreakEvent {
+        self.cycles_per_frame = cycles_per_frame.max(1);
+        self.execute(cpu, u32::MAX, Until::FrameEnd, false)
+    }
+    
+    /// Exécuter exactement une instruction
+    pub fn step_instruction(&mut self, cpu: &mut Cpu) -> BreakEvent {
+        self.execute(cpu, 1, Until::Break, true)
+    }
+    
+    /// Exécuter un 2nnn et tout le sous-programme appelé comme une seule instruction
+    pub fn step_over(&mut self, cpu: &mut Cpu, max_instructions: u32) -> BreakEvent {
+        let opcode = ((cpu.memory.peek_byte(cpu.pc) as u16) << 8) | cpu.memory.peek_byte(cpu.pc.wrapping_add(1)) as u16;
+        if opcode & 0xF000 != 0x2000 {
+            return self.step_instruction(cpu);
+        }
+        
+        let until = Until::Return { pc: cpu.pc.wrapping_add(2), sp: cpu.sp };
+        self.execute(cpu, max_instructions, until, true)
+    }
+    
+    /// Continuer jusqu'au 00EE qui quitte le sous-programme courant
+    pub fn step_out(&mut self, cpu: &mut Cpu, max_instructions: u32) -> BreakEvent {
+        if cpu.sp == 0 {
+            return self.step_instruction(cpu);
+        }
+        self.execute(cpu, max_instructions, Until::StackBelow(cpu.sp), true)
+    }
+    
+    /// Continuer jusqu'au prochain breakpoint, watchpoint ou arrêt du CPU
+    pub fn run_until_break(&mut self, cpu: &mut Cpu, max_instructions: u32) -> BreakEvent {
+        self.execute(cpu, max_instructions, Until::Break, false)
+    }
+    
+    fn execute(&mut self, cpu: &mut Cpu, max_instructions: u32, until: Until, stepping: bool) -> BreakEvent {
+        // Reprendre depuis un breakpoint (ou faire un pas) ne doit pas s'y arrêter aussitôt
+        let mut skip_breakpoint = stepping || self.resume_pc == Some(cpu.pc);
+        let mut instructions = 0;
+        cpu.memory.take_watch_hit();
+        
+        let event = loop {
+            if let Some(kind) = Self::cpu_stopped(cpu) {
+                if matches!(until, Until::FrameEnd) && (self.frame_cycles > 0 || instructions == 0) {
+                    // Comme Cpu::run_frame : les timers tournent même CPU arrêté
+                    cpu.tick_timers();
+                    self.frame_cycles = 0;
+                }
+                break self.event(cpu, kind, cpu.pc, 0, instructions);
+            }
+            
+            if !skip_breakpoint && let Some(breakpoint) = self.breakpoints.iter().find(|b| b.hit(cpu)) {
+                break self.event(cpu, BreakKind::Breakpoint, breakpoint.address, 0, instructions);
+            }
+            skip_breakpoint = false;
+            
+            if instructions >= max_instructions {
+                break self.event(cpu, BreakKind::InstructionLimit, cpu.pc, 0, instructions);
+            }
+            
+            cpu.cycle();
+            instructions += 1;
+            
+            self.frame_cycles += 1;
+            let frame_ended = self.frame_cycles >= self.cycles_per_frame;
+            if frame_ended {
+                cpu.tick_timers();
+                self.frame_cycles = 0;
+            }
+            
+            if let Some(hit) = cpu.memory.take_watch_hit() {
+                let kind = if hit.write { BreakKind::WatchWrite } else { BreakKind::WatchRead };
+                break self.event(cpu, kind, hit.address, hit.value, instructions);
+            }
+            
+            let done = match until {
+                Until::FrameEnd => frame_ended,
+                Until::Return { pc, sp } => cpu.pc == pc && cpu.sp == sp,
+                Until::StackBelow(sp) => cpu.sp < sp,
+                Until::Break => stepping && instructions >= max_instructions,
+            };
+            if done {
+                let kind = if matches!(until, Until::FrameEnd) { BreakKind::FrameComplete } else { BreakKind::Step };
+                break self.event(cpu, kind, cpu.pc, 0, instructions);
+            }
+        };
+        
+        self.resume_pc = match event.kind {
+            BreakKind::Breakpoint | BreakKind::WatchRead | BreakKind::WatchWrite => Some(cpu.pc),
+            _ => None,
+        };
+        if event.kind != BreakKind::FrameComplete {
+            self.last_break = Some(event);
+        }
+        event
+    }
+    
+    fn cpu_stopped(cpu: &Cpu) -> Option<BreakKind> {
+        if cpu.error_limit_reached() {
+            Some(BreakKind::ErrorLimit)
+        } else if cpu.is_halted() {
+            Some(BreakKind::Halted)
+        } else {
+            None
+        }
+    }
+    
+    fn event(&self, cpu: &Cpu, kind: BreakKind, address: u16, value: u8, instructions: u32) -> BreakEvent {
+        BreakEvent {
+            kind,
+            pc: cpu.pc,
+            address,
+            value,
+            instructions,
+        }
+    }
+}
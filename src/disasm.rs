@@ -0,0 +1,220 @@
+//! Désassembleur Chip-8 / SCHIP / XO-CHIP
+//! Deux syntaxes : mnémoniques classiques (style Cowgod, `DRW V1, V2, 5`)
+//! ou syntaxe Octo (`sprite v1 v2 5`). Les extensions ne sont décodées que
+//! si la plateforme les reconnaît, sinon l'opcode est affiché comme donnée
+
+use crate::{Memory, Platform};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Syntax {
+    #[default]
+    Classic,
+    Octo,
+}
+
+impl Syntax {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "classic" | "cowgod" | "asm" => Some(Syntax::Classic),
+            "octo" => Some(Syntax::Octo),
+            _ => None,
+        }
+    }
+}
+
+/// Une instruction désassemblée
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisasmLine {
+    pub address: u16,
+    pub opcode: u16,
+    /// Second mot de F000 nnnn (XO-CHIP)
+    pub operand: Option<u16>,
+    pub text: String,
+}
+
+impl DisasmLine {
+    /// Taille en octets (4 pour F000 nnnn)
+    pub fn size(&self) -> u16 {
+        if self.operand.is_some() { 4 } else { 2 }
+    }
+}
+
+/// Désassembler un opcode isolé ; F000 affiche `nnnn` faute de second mot
+pub fn disassemble(opcode: u16, platform: Platform, syntax: Syntax) -> String {
+    decode(opcode, None, platform, syntax)
+}
+
+/// Désassembler `count` instructions à partir de `start`
+pub fn disassemble_range(memory: &Memory, start: u16, count: usize, platform: Platform, syntax: Syntax) -> Vec<DisasmLine> {
+    let mut lines = Vec::with_capacity(count);
+    let mut address = start as usize;
+    
+    while lines.len() < count && address + 1 < memory.size() {
+        let opcode = read_word(memory, address as u16);
+        let operand = (platform.has_xo_opcodes() && opcode == 0xF000 && address + 3 < memory.size())
+            .then(|| read_word(memory, (address + 2) as u16));
+        
+        let line = DisasmLine {
+            address: address as u16,
+            opcode,
+            operand,
+            text: decode(opcode, operand, platform, syntax),
+        };
+        address += line.size() as usize;
+        lines.push(line);
+    }
+    lines
+}
+
+fn read_word(memory: &Memory, address: u16) -> u16 {
+    ((memory.peek_byte(address) as u16) << 8) | memory.peek_byte(address.wrapping_add(1)) as u16
+}
+
+fn decode(opcode: u16, operand: Option<u16>, platform: Platform, syntax: Syntax) -> String {
+    let x = ((opcode & 0x0F00) >> 8) as u8;
+    let y = ((opcode & 0x00F0) >> 4) as u8;
+    let n = (opcode & 0x000F) as u8;
+    let kk = (opcode & 0x00FF) as u8;
+    let nnn = opcode & 0x0FFF;
+    
+    let schip = platform.has_schip_opcodes();
+    let xo = platform.has_xo_opcodes();
+    let octo = syntax == Syntax::Octo;
+    
+    // Registres : V1 en classique, v1 en Octo
+    let reg = |r: u8| if octo { format!("v{:x}", r) } else { format!("V{:X}", r) };
+    let vx = reg(x);
+    let vy = reg(y);
+    
+    match (opcode & 0xF000, octo) {
+        (0x0000, _) => match opcode {
+            0x00E0 => pick(octo, "clear", "CLS"),
+            0x00EE => pick(octo, "return", "RET"),
+            0x00FB if schip => pick(octo, "scroll-right", "SCR"),
+            0x00FC if schip => pick(octo, "scroll-left", "SCL"),
+            0x00FD if schip => pick(octo, "exit", "EXIT"),
+            0x00FE if schip => pick(octo, "lores", "LOW"),
+            0x00FF if schip => pick(octo, "hires", "HIGH"),
+            _ if schip && opcode & 0xFFF0 == 0x00C0 => {
+                if octo { format!("scroll-down {}", n) } else { format!("SCD {}", n) }
+            },
+            _ if xo && opcode & 0xFFF0 == 0x00D0 => {
+                if octo { format!("scroll-up {}", n) } else { format!("SCU {}", n) }
+            },
+            // Routine machine du VIP : Octo n'a pas d'équivalent
+            _ if octo => data(opcode, syntax),
+            _ => format!("SYS 0x{:03X}", nnn),
+        },
+        (0x1000, true) => format!("jump 0x{:03X}", nnn),
+        (0x1000, false) => format!("JP 0x{:03X}", nnn),
+        (0x2000, true) => format!(":call 0x{:03X}", nnn),
+        (0x2000, false) => format!("CALL 0x{:03X}", nnn),
+        (0x3000, true) => format!("if {} != 0x{:02X} then", vx, kk),
+        (0x3000, false) => format!("SE {}, 0x{:02X}", vx, kk),
+        (0x4000, true) => format!("if {} == 0x{:02X} then", vx, kk),
+        (0x4000, false) => format!("SNE {}, 0x{:02X}", vx, kk),
+        (0x5000, _) => match n {
+            0x0 if octo => format!("if {} != {} then", vx, vy),
+            0x0 => format!("SE {}, {}", vx, vy),
+            0x2 if xo && octo => format!("save {} - {}", vx, vy),
+            0x2 if xo => format!("LD [I], {}-{}", vx, vy),
+            0x3 if xo && octo => format!("load {} - {}", vx, vy),
+            0x3 if xo => format!("LD {}-{}, [I]", vx, vy),
+            _ => data(opcode, syntax),
+        },
+        (0x6000, true) => format!("{} := 0x{:02X}", vx, kk),
+        (0x6000, false) => format!("LD {}, 0x{:02X}", vx, kk),
+        (0x7000, true) => format!("{} += 0x{:02X}", vx, kk),
+        (0x7000, false) => format!("ADD {}, 0x{:02X}", vx, kk),
+        (0x8000, _) => {
+            let (octo_op, classic_op) = match n {
+                0x0 => (":=", "LD"),
+                0x1 => ("|=", "OR"),
+                0x2 => ("&=", "AND"),
+                0x3 => ("^=", "XOR"),
+                0x4 => ("+=", "ADD"),
+                0x5 => ("-=", "SUB"),
+                0x6 => (">>=", "SHR"),
+                0x7 => ("=-", "SUBN"),
+                0xE => ("<<=", "SHL"),
+                _ => return data(opcode, syntax),
+            };
+            if octo {
+                format!("{} {} {}", vx, octo_op, vy)
+            } else {
+                format!("{} {}, {}", classic_op, vx, vy)
+            }
+        },
+        (0x9000, _) if n != 0 => data(opcode, syntax),
+        (0x9000, true) => format!("if {} == {} then", vx, vy),
+        (0x9000, false) => format!("SNE {}, {}", vx, vy),
+        (0xA000, true) => format!("i := 0x{:03X}", nnn),
+        (0xA000, false) => format!("LD I, 0x{:03X}", nnn),
+        (0xB000, true) => format!("jump0 0x{:03X}", nnn),
+        (0xB000, false) => format!("JP V0, 0x{:03X}", nnn),
+        (0xC000, true) => format!("{} := random 0x{:02X}", vx, kk),
+        (0xC000, false) => format!("RND {}, 0x{:02X}", vx, kk),
+        (0xD000, _) if n == 0 && !schip => data(opcode, syntax),
+        (0xD000, true) => format!("sprite {} {} {}", vx, vy, n),
+        (0xD000, false) => format!("DRW {}, {}, {}", vx, vy, n),
+        (0xE000, _) => match kk {
+            0x9E if octo => format!("if {} -key then", vx),
+            0x9E => format!("SKP {}", vx),
+            0xA1 if octo => format!("if {} key then", vx),
+            0xA1 => format!("SKNP {}", vx),
+            _ => data(opcode, syntax),
+        },
+        (0xF000, _) => decode_fxxx(opcode, operand, platform, syntax, &vx),
+        _ => unreachable!(),
+    }
+}
+
+fn decode_fxxx(opcode: u16, operand: Option<u16>, platform: Platform, syntax: Syntax, vx: &str) -> String {
+    let x = ((opcode & 0x0F00) >> 8) as u8;
+    let kk = (opcode & 0x00FF) as u8;
+    let schip = platform.has_schip_opcodes();
+    let xo = platform.has_xo_opcodes();
+    let octo = syntax == Syntax::Octo;
+    
+    // F000 nnnn et Fn01 portent sur le numéro, pas sur un registre
+    if xo && opcode == 0xF000 {
+        let address = operand.map_or(String::from("nnnn"), |address| format!("0x{:04X}", address));
+        return if octo { format!("i := long {}", address) } else { format!("LD I, long {}", address) };
+    }
+    if xo && kk == 0x01 {
+        return if octo { format!("plane {}", x) } else { format!("PLANE {}", x) };
+    }
+    if xo && opcode == 0xF002 {
+        return pick(octo, "audio", "AUDIO");
+    }
+    
+    let (octo_text, classic_text) = match kk {
+        0x07 => (format!("{} := delay", vx), format!("LD {}, DT", vx)),
+        0x0A => (format!("{} := key", vx), format!("LD {}, K", vx)),
+        0x15 => (format!("delay := {}", vx), format!("LD DT, {}", vx)),
+        0x18 => (format!("buzzer := {}", vx), format!("LD ST, {}", vx)),
+        0x1E => (format!("i += {}", vx), format!("ADD I, {}", vx)),
+        0x29 => (format!("i := hex {}", vx), format!("LD F, {}", vx)),
+        0x30 if schip => (format!("i := bighex {}", vx), format!("LD HF, {}", vx)),
+        0x33 => (format!("bcd {}", vx), format!("LD B, {}", vx)),
+        0x3A if xo => (format!("pitch := {}", vx), format!("PITCH {}", vx)),
+        0x55 => (format!("save {}", vx), format!("LD [I], {}", vx)),
+        0x65 => (format!("load {}", vx), format!("LD {}, [I]", vx)),
+        0x75 if schip => (format!("saveflags {}", vx), format!("LD R, {}", vx)),
+        0x85 if schip => (format!("loadflags {}", vx), format!("LD {}, R", vx)),
+        _ => return data(opcode, syntax),
+    };
+    if octo { octo_text } else { classic_text }
+}
+
+fn pick(octo: bool, octo_text: &str, classic_text: &str) -> String {
+    String::from(if octo { octo_text } else { classic_text })
+}
+
+/// Opcode inconnu ou donnée (sprites au milieu du code)
+fn data(opcode: u16, syntax: Syntax) -> String {
+    match syntax {
+        Syntax::Octo => format!("0x{:02X} 0x{:02X}", opcode >> 8, opcode & 0xFF),
+        Syntax::Classic => format!("DW 0x{:04X}", opcode),
+    }
+}
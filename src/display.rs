@@ -1,82 +1,290 @@
-//! Écran 64x32 pixels noir et blanc
+//! Écran 64x32 pixels (128x64 en mode haute résolution SCHIP)
+//! Chaque pixel porte deux bitplanes XO-CHIP : 4 couleurs possibles
+
+use crate::host::log;
+use crate::savestate::{StateReader, StateWriter};
 
 pub const DISPLAY_WIDTH: usize = 64;
 pub const DISPLAY_HEIGHT: usize = 32;
 pub const DISPLAY_PIXELS: usize = DISPLAY_WIDTH * DISPLAY_HEIGHT;
 
+pub const HIRES_WIDTH: usize = 128;
+pub const HIRES_HEIGHT: usize = 64;
+
+/// Masque des deux bitplanes XO-CHIP
+pub const ALL_PLANES: u8 = 0b11;
+
 pub struct Display {
-    pixels: [bool; DISPLAY_PIXELS],
+    /// Bits de plans par pixel (bit 0 = plan 1, bit 1 = plan 2)
+    pixels: Vec<u8>,
+    width: usize,
+    height: usize,
+    /// Plans sélectionnés par Fn01, plan 1 seul par défaut
+    planes: u8,
+}
+
+impl Default for Display {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Display {
     pub fn new() -> Self {
         Display {
-            pixels: [false; DISPLAY_PIXELS],
+            pixels: vec![0; DISPLAY_PIXELS],
+            width: DISPLAY_WIDTH,
+            height: DISPLAY_HEIGHT,
+            planes: 1,
         }
     }
     
+    /// Effacer les plans sélectionnés (00E0)
     pub fn clear(&mut self) {
-        self.pixels = [false; DISPLAY_PIXELS];
+        let keep = !self.planes;
+        for pixel in self.pixels.iter_mut() {
+            *pixel &= keep;
+        }
+    }
+    
+    /// Plans sélectionnés (masque 0-3)
+    pub fn planes(&self) -> u8 {
+        self.planes
+    }
+    
+    /// Fn01 - Sélectionner les plans de dessin
+    pub fn select_planes(&mut self, planes: u8) {
+        self.planes = planes & ALL_PLANES;
+    }
+    
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    
+    pub fn is_high_resolution(&self) -> bool {
+        self.width == HIRES_WIDTH
+    }
+    
+    /// Basculer entre 64x32 et 128x64 (00FE/00FF), tous les plans sont effacés
+    pub fn set_high_resolution(&mut self, enabled: bool) {
+        let (width, height) = if enabled {
+            (HIRES_WIDTH, HIRES_HEIGHT)
+        } else {
+            (DISPLAY_WIDTH, DISPLAY_HEIGHT)
+        };
+        
+        self.width = width;
+        self.height = height;
+        self.pixels = vec![0; width * height];
     }
     
+    /// Pixel allumé sur au moins un plan
     pub fn get_pixel(&self, x: usize, y: usize) -> bool {
-        if x < DISPLAY_WIDTH && y < DISPLAY_HEIGHT {
-            self.pixels[y * DISPLAY_WIDTH + x]
+        self.get_plane_pixel(x, y) != 0
+    }
+    
+    /// Index de couleur du pixel (0-3, combinaison des deux plans)
+    pub fn get_plane_pixel(&self, x: usize, y: usize) -> u8 {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x]
         } else {
-            false
+            0
         }
     }
     
+    /// Allumer ou éteindre un pixel sur les plans sélectionnés
     pub fn set_pixel(&mut self, x: usize, y: usize, value: bool) {
-        let wrapped_x = x % DISPLAY_WIDTH;
-        let wrapped_y = y % DISPLAY_HEIGHT;
-        self.pixels[wrapped_y * DISPLAY_WIDTH + wrapped_x] = value;
+        let wrapped_x = x % self.width;
+        let wrapped_y = y % self.height;
+        let pixel = &mut self.pixels[wrapped_y * self.width + wrapped_x];
+        if value {
+            *pixel |= self.planes;
+        } else {
+            *pixel &= !self.planes;
+        }
+    }
+    
+    /// Nombre de plans sélectionnés, chacun consomme sa part des données du sprite
+    pub fn plane_count(&self) -> usize {
+        self.planes.count_ones() as usize
+    }
+    
+    /// Dessiner un sprite 8xN en XOR sur chaque plan sélectionné, `clip` coupe
+    /// au bord au lieu de boucler. Retourne le nombre de lignes en collision
+    pub fn draw_sprite(&mut self, x: usize, y: usize, sprite_data: &[u8], clip: bool) -> usize {
+        let rows: Vec<u16> = sprite_data.iter()
+            .map(|&byte| (byte as u16) << 8)
+            .collect();
+        self.draw_planes(x, y, &rows, 8, clip)
     }
     
-    pub fn draw_sprite(&mut self, x: usize, y: usize, sprite_data: &[u8]) -> bool {
-        let mut collision = false;
+    /// Dessiner un sprite 16x16 SCHIP (Dxy0), 2 bytes par ligne
+    pub fn draw_sprite_16(&mut self, x: usize, y: usize, sprite_data: &[u8], clip: bool) -> usize {
+        let rows: Vec<u16> = sprite_data.chunks(2)
+            .map(|pair| ((pair[0] as u16) << 8) | *pair.get(1).unwrap_or(&0) as u16)
+            .collect();
+        self.draw_planes(x, y, &rows, 16, clip)
+    }
+    
+    /// Les données du sprite se suivent : plan 1 puis plan 2
+    fn draw_planes(&mut self, x: usize, y: usize, rows: &[u16], sprite_width: usize, clip: bool) -> usize {
+        let plane_count = self.plane_count();
+        if plane_count == 0 {
+            return 0;
+        }
         
-        for (row, &sprite_byte) in sprite_data.iter().enumerate() {
-            for col in 0..8 {
-                let sprite_pixel = (sprite_byte >> (7 - col)) & 1 == 1;
+        let rows_per_plane = rows.len() / plane_count;
+        let mut collided_rows = 0;
+        let mut plane_rows = rows.chunks(rows_per_plane.max(1));
+        
+        for plane in [1u8, 2u8] {
+            if self.planes & plane == 0 {
+                continue;
+            }
+            if let Some(data) = plane_rows.next() {
+                collided_rows = collided_rows.max(self.draw_rows(x, y, data, sprite_width, clip, plane));
+            }
+        }
+        
+        collided_rows
+    }
+    
+    fn draw_rows(&mut self, x: usize, y: usize, rows: &[u16], sprite_width: usize, clip: bool, plane: u8) -> usize {
+        let mut collided_rows = 0;
+        
+        // La position de départ boucle toujours, seul le débordement est coupé
+        let x = x % self.width;
+        let y = y % self.height;
+        
+        for (row, &sprite_row) in rows.iter().enumerate() {
+            let mut collision = false;
+            
+            for col in 0..sprite_width {
+                let sprite_pixel = (sprite_row >> (15 - col)) & 1 == 1;
                 
                 if sprite_pixel {
-                    let pixel_x = (x + col) % DISPLAY_WIDTH;
-                    let pixel_y = (y + row) % DISPLAY_HEIGHT;
-                    let pixel_index = pixel_y * DISPLAY_WIDTH + pixel_x;
+                    if clip && (x + col >= self.width || y + row >= self.height) {
+                        continue;
+                    }
+                    
+                    let pixel_x = (x + col) % self.width;
+                    let pixel_y = (y + row) % self.height;
+                    let pixel_index = pixel_y * self.width + pixel_x;
                     
-                    let old_pixel = self.pixels[pixel_index];
-                    self.pixels[pixel_index] = old_pixel ^ sprite_pixel;
+                    let old_pixel = self.pixels[pixel_index] & plane != 0;
+                    self.pixels[pixel_index] ^= plane;
                     
-                    if old_pixel && !self.pixels[pixel_index] {
+                    if old_pixel {
                         collision = true;
                     }
                 }
             }
+            
+            if collision {
+                collided_rows += 1;
+            }
         }
         
-        collision
+        collided_rows
+    }
+    
+    /// Déplacer le contenu des plans sélectionnés de (dx, dy), les bords se remplissent de vide
+    fn scroll(&mut self, dx: isize, dy: isize) {
+        let mask = self.planes;
+        let source = self.pixels.clone();
+        let (width, height) = (self.width as isize, self.height as isize);
+        
+        for y in 0..height {
+            for x in 0..width {
+                let (src_x, src_y) = (x - dx, y - dy);
+                let moved = if (0..width).contains(&src_x) && (0..height).contains(&src_y) {
+                    source[(src_y * width + src_x) as usize] & mask
+                } else {
+                    0
+                };
+                let index = (y * width + x) as usize;
+                self.pixels[index] = (source[index] & !mask) | moved;
+            }
+        }
+    }
+    
+    /// 00Cn - Défilement de n lignes vers le bas
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll(0, lines.min(self.height) as isize);
+    }
+    
+    /// 00Dn - Défilement de n lignes vers le haut (XO-CHIP)
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll(0, -(lines.min(self.height) as isize));
+    }
+    
+    /// 00FB - Défilement de 4 pixels vers la droite
+    pub fn scroll_right(&mut self) {
+        self.scroll(4, 0);
+    }
+    
+    /// 00FC - Défilement de 4 pixels vers la gauche
+    pub fn scroll_left(&mut self) {
+        self.scroll(-4, 0);
     }
     
+    /// Buffer monochrome : 255 si le pixel est allumé sur un plan
     pub fn get_buffer(&self) -> Vec<u8> {
         self.pixels.iter()
-            .map(|&pixel| if pixel { 255 } else { 0 })
+            .map(|&pixel| if pixel != 0 { 255 } else { 0 })
             .collect()
     }
     
-    #[allow(dead_code)]
-    pub fn debug_print(&self) {
-        let mut screen = String::new();
-        for y in 0..DISPLAY_HEIGHT {
-            for x in 0..DISPLAY_WIDTH {
-                screen.push(if self.get_pixel(x, y) { '█' } else { ' ' });
+    /// Buffer des index de couleur (0-3) pour le rendu quatre couleurs XO-CHIP
+    pub fn get_plane_buffer(&self) -> Vec<u8> {
+        self.pixels.clone()
+    }
+    
+    /// Rendu texte de l'écran, une ligne par rangée de pixels
+    pub fn to_text(&self, on: char, off: char) -> String {
+        let mut screen = String::with_capacity((self.width + 1) * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                screen.push(if self.get_pixel(x, y) { on } else { off });
             }
             screen.push('\n');
         }
-        web_sys::console::log_1(&screen.into());
+        screen
+    }
+    
+    #[allow(dead_code)]
+    pub fn debug_print(&self) {
+        log(&self.to_text('█', ' '));
     }
     
     pub fn count_active_pixels(&self) -> usize {
-        self.pixels.iter().filter(|&&pixel| pixel).count()
+        self.pixels.iter().filter(|&&pixel| pixel != 0).count()
+    }
+}
+
+impl Display {
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        writer.bool(self.is_high_resolution());
+        writer.u8(self.planes);
+        writer.vec(&self.pixels);
     }
-}
\ No newline at end of file
+    
+    pub fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        let high_resolution = reader.bool()?;
+        let planes = reader.u8()?;
+        let pixels = reader.vec()?;
+        
+        self.set_high_resolution(high_resolution);
+        if pixels.len() != self.pixels.len() {
+            return Err(format!("Taille d'écran invalide: {} pixels", pixels.len()));
+        }
+        
+        self.select_planes(planes);
+        self.pixels = pixels;
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+//! Erreurs d'exécution du CPU et raisons d'arrêt
+//! Chaque erreur garde le PC et l'opcode fautifs ; le CPU en conserve un
+//! historique borné que l'interface peut afficher
+
+use std::fmt;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::symbols::SymbolMap;
+
+/// Nature d'une erreur d'exécution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CpuErrorKind {
+    /// Opcode non reconnu par la plateforme
+    UnknownOpcode,
+    /// PC hors de la zone programme ou impair
+    InvalidPc { address: u16 },
+    /// Saut, appel ou retour vers une adresse hors programme
+    InvalidJump { target: u16 },
+    /// Appel alors que la pile est pleine
+    StackOverflow,
+    /// Retour alors que la pile est vide
+    StackUnderflow,
+    /// Lecture ou écriture de `length` octets en I qui sort de la mémoire
+    MemoryOutOfBounds { address: u16, length: u16 },
+    /// Registre refusé par l'instruction (Fx75/Fx85 au-delà de V7 en SCHIP)
+    InvalidRegister { register: u8 },
+    /// Écriture dans une zone protégée (fonts)
+    ProtectedWrite { address: u16 },
+    /// Ex9E/ExA1 avec un numéro de touche au-delà de F
+    InvalidKey { key: u8 },
+}
+
+/// Erreur survenue pendant l'exécution d'une instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CpuError {
+    /// Adresse de l'instruction fautive
+    pub pc: u16,
+    pub opcode: u16,
+    pub kind: CpuErrorKind,
+}
+
+impl CpuErrorKind {
+    /// Nom stable, pour JavaScript et les outils
+    pub fn name(self) -> &'static str {
+        match self {
+            CpuErrorKind::UnknownOpcode => "unknown_opcode",
+            CpuErrorKind::InvalidPc { .. } => "invalid_pc",
+            CpuErrorKind::InvalidJump { .. } => "invalid_jump",
+            CpuErrorKind::StackOverflow => "stack_overflow",
+            CpuErrorKind::StackUnderflow => "stack_underflow",
+            CpuErrorKind::MemoryOutOfBounds { .. } => "memory_out_of_bounds",
+            CpuErrorKind::InvalidRegister { .. } => "invalid_register",
+            CpuErrorKind::ProtectedWrite { .. } => "protected_write",
+            CpuErrorKind::InvalidKey { .. } => "invalid_key",
+        }
+    }
+}
+
+impl CpuError {
+    fn message(&self) -> String {
+        match self.kind {
+            CpuErrorKind::UnknownOpcode => String::from("Opcode inconnu"),
+            CpuErrorKind::InvalidPc { address } => format!("PC invalide: 0x{:04X}", address),
+            CpuErrorKind::InvalidJump { target } => format!("Saut invalide vers 0x{:04X}", target),
+            CpuErrorKind::StackOverflow => String::from("Pile pleine"),
+            CpuErrorKind::StackUnderflow => String::from("Retour avec une pile vide"),
+            CpuErrorKind::MemoryOutOfBounds { address, length } => {
+                format!("Accès hors mémoire: {} octets en 0x{:04X}", length, address)
+            },
+            CpuErrorKind::InvalidRegister { register } => format!("Registre V{:X} non autorisé", register),
+            CpuErrorKind::ProtectedWrite { address } => format!("Écriture en zone protégée: 0x{:04X}", address),
+            CpuErrorKind::InvalidKey { key } => format!("Touche invalide: 0x{:02X}", key),
+        }
+    }
+    
+    /// Message avec l'emplacement symbolique (`main+0x12 (game.8o:42)`)
+    pub fn describe(&self, symbols: &SymbolMap) -> String {
+        format!("{} [{:04X}] à {}", self.message(), self.opcode, symbols.describe(self.pc))
+    }
+}
+
+impl fmt::Display for CpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{:04X}] à 0x{:04X}", self.message(), self.opcode, self.pc)
+    }
+}
+
+impl std::error::Error for CpuError {}
+
+/// Pourquoi le CPU (ou l'émulateur) s'est arrêté
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HaltReason {
+    /// 00FD (SCHIP) : fin normale du programme
+    Exit00FD,
+    /// Opcode 0000, souvent de la mémoire vide exécutée
+    Opcode0000,
+    /// 00EE avec une pile vide
+    StackUnderflow,
+    /// 00EE vers une adresse de retour hors programme
+    InvalidReturn,
+    /// PC arrivé en fin de mémoire
+    EndOfMemory,
+    /// Trop d'erreurs d'exécution
+    ErrorLimit,
+    /// Breakpoint ou watchpoint du debugger (l'émulateur est en pause, pas le CPU)
+    Breakpoint,
+    /// Première erreur en politique d'exécution stricte
+    Trap,
+}
+
+impl HaltReason {
+    pub fn describe(self) -> &'static str {
+        match self {
+            HaltReason::Exit00FD => "Programme terminé (00FD)",
+            HaltReason::Opcode0000 => "Opcode 0000 exécuté (mémoire vide ?)",
+            HaltReason::StackUnderflow => "Retour de sous-programme avec une pile vide",
+            HaltReason::InvalidReturn => "Adresse de retour invalide",
+            HaltReason::EndOfMemory => "Fin de la mémoire atteinte",
+            HaltReason::ErrorLimit => "Trop d'erreurs d'exécution",
+            HaltReason::Breakpoint => "Arrêt sur breakpoint",
+            HaltReason::Trap => "Erreur d'exécution (politique stricte)",
+        }
+    }
+    
+    pub fn id(self) -> u8 {
+        match self {
+            HaltReason::Exit00FD => 0,
+            HaltReason::Opcode0000 => 1,
+            HaltReason::StackUnderflow => 2,
+            HaltReason::InvalidReturn => 3,
+            HaltReason::EndOfMemory => 4,
+            HaltReason::ErrorLimit => 5,
+            HaltReason::Breakpoint => 6,
+            HaltReason::Trap => 7,
+        }
+    }
+    
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(HaltReason::Exit00FD),
+            1 => Some(HaltReason::Opcode0000),
+            2 => Some(HaltReason::StackUnderflow),
+            3 => Some(HaltReason::InvalidReturn),
+            4 => Some(HaltReason::EndOfMemory),
+            5 => Some(HaltReason::ErrorLimit),
+            6 => Some(HaltReason::Breakpoint),
+            7 => Some(HaltReason::Trap),
+            _ => None,
+        }
+    }
+}
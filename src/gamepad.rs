@@ -0,0 +1,211 @@
+//! Manettes : boutons et axes analogiques vers les touches Chip-8
+//! L'état des manettes est fourni par l'hôte (Gamepad API du navigateur avec la
+//! feature `gamepad`), le cœur ne fait que la correspondance
+
+use crate::json::{self, JsonValue};
+
+/// Zone morte par défaut des sticks, en fraction de la course
+pub const DEFAULT_DEAD_ZONE: f32 = 0.4;
+
+/// Noms des boutons du mapping standard de la Gamepad API, par index
+const BUTTON_NAMES: [&str; 17] = [
+    "a", "b", "x", "y", "lb", "rb", "lt", "rt",
+    "select", "start", "ls", "rs", "up", "down", "left", "right", "home",
+];
+
+/// Noms des axes du mapping standard : stick gauche puis stick droit
+const AXIS_NAMES: [&str; 4] = ["lx", "ly", "rx", "ry"];
+
+/// Bouton ou demi-axe d'une manette
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GamepadInput {
+    Button(u8),
+    /// Axe poussé vers le négatif (gauche, haut) ou le positif (droite, bas)
+    Axis { axis: u8, positive: bool },
+}
+
+impl GamepadInput {
+    /// "a", "up", "ly-", "rx+", ou générique "button5", "axis4+"
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if let Some(direction) = name.strip_suffix('-').map(|axis| (axis, false))
+            .or_else(|| name.strip_suffix('+').map(|axis| (axis, true)))
+        {
+            let (axis, positive) = direction;
+            let index = match AXIS_NAMES.iter().position(|&known| known == axis) {
+                Some(index) => index as u8,
+                None => axis.strip_prefix("axis")?.parse().ok()?,
+            };
+            return Some(GamepadInput::Axis { axis: index, positive });
+        }
+        match BUTTON_NAMES.iter().position(|&known| known == name) {
+            Some(index) => Some(GamepadInput::Button(index as u8)),
+            None => name.strip_prefix("button")?.parse().ok().map(GamepadInput::Button),
+        }
+    }
+    
+    pub fn name(self) -> String {
+        match self {
+            GamepadInput::Button(index) => match BUTTON_NAMES.get(index as usize) {
+                Some(name) => String::from(*name),
+                None => format!("button{}", index),
+            },
+            GamepadInput::Axis { axis, positive } => {
+                let sign = if positive { '+' } else { '-' };
+                match AXIS_NAMES.get(axis as usize) {
+                    Some(name) => format!("{}{}", name, sign),
+                    None => format!("axis{}{}", axis, sign),
+                }
+            },
+        }
+    }
+}
+
+/// Boutons enfoncés et position des axes (-1.0 à 1.0) d'une manette
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GamepadState {
+    pub buttons: Vec<bool>,
+    pub axes: Vec<f32>,
+}
+
+/// Correspondance entrées de manette -> touches Chip-8, plusieurs entrées par touche
+#[derive(Clone, Debug, PartialEq)]
+pub struct GamepadMap {
+    bindings: Vec<(GamepadInput, u8)>,
+    dead_zone: f32,
+}
+
+impl Default for GamepadMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GamepadMap {
+    /// Croix et stick gauche sur 5/7/8/9 (le WASD d'Octo), A sur 6 et B sur 4
+    pub fn new() -> Self {
+        Self::from_names(&[
+            ("up", 0x5), ("ly-", 0x5), ("left", 0x7), ("lx-", 0x7),
+            ("down", 0x8), ("ly+", 0x8), ("right", 0x9), ("lx+", 0x9),
+            ("a", 0x6), ("b", 0x4),
+        ])
+    }
+    
+    /// Presets "octo" (défaut), "vip" (2/4/6/8, tir sur 5) et "pong"
+    /// (deux raquettes : stick gauche sur 1/4, stick droit sur C/D)
+    pub fn from_preset(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "octo" | "default" => Some(Self::new()),
+            "vip" => Some(Self::from_names(&[
+                ("up", 0x2), ("ly-", 0x2), ("left", 0x4), ("lx-", 0x4),
+                ("down", 0x8), ("ly+", 0x8), ("right", 0x6), ("lx+", 0x6),
+                ("a", 0x5), ("b", 0x0),
+            ])),
+            "pong" => Some(Self::from_names(&[
+                ("up", 0x1), ("ly-", 0x1), ("down", 0x4), ("ly+", 0x4),
+                ("x", 0xC), ("ry-", 0xC), ("a", 0xD), ("ry+", 0xD),
+            ])),
+            _ => None,
+        }
+    }
+    
+    /// Aucune entrée associée
+    pub fn empty() -> Self {
+        GamepadMap { bindings: Vec::new(), dead_zone: DEFAULT_DEAD_ZONE }
+    }
+    
+    fn from_names(names: &[(&str, u8)]) -> Self {
+        let bindings = names.iter()
+            .filter_map(|&(name, key)| GamepadInput::from_name(name).map(|input| (input, key)))
+            .collect();
+        GamepadMap { bindings, dead_zone: DEFAULT_DEAD_ZONE }
+    }
+    
+    pub fn dead_zone(&self) -> f32 {
+        self.dead_zone
+    }
+    
+    /// Course minimale d'un stick avant d'appuyer la touche, bornée à 0.05-0.95
+    pub fn set_dead_zone(&mut self, dead_zone: f32) {
+        self.dead_zone = dead_zone.clamp(0.05, 0.95);
+    }
+    
+    /// Associer une entrée (remplace son ancienne association), false si `key` > F
+    pub fn bind(&mut self, input: GamepadInput, key: u8) -> bool {
+        if key > 0xF {
+            return false;
+        }
+        self.unbind(input);
+        self.bindings.push((input, key));
+        true
+    }
+    
+    pub fn unbind(&mut self, input: GamepadInput) -> bool {
+        let before = self.bindings.len();
+        self.bindings.retain(|&(bound, _)| bound != input);
+        self.bindings.len() != before
+    }
+    
+    pub fn inputs_for(&self, key: u8) -> Vec<GamepadInput> {
+        self.bindings.iter().filter(|&&(_, bound)| bound == key).map(|&(input, _)| input).collect()
+    }
+    
+    /// Touches Chip-8 enfoncées par une manette, bit n = touche n
+    pub fn pressed_keys(&self, state: &GamepadState) -> u16 {
+        self.bindings.iter()
+            .filter(|&&(input, _)| match input {
+                GamepadInput::Button(index) => state.buttons.get(index as usize).copied().unwrap_or(false),
+                GamepadInput::Axis { axis, positive } => {
+                    let value = state.axes.get(axis as usize).copied().unwrap_or(0.0);
+                    if positive { value >= self.dead_zone } else { value <= -self.dead_zone }
+                },
+            })
+            .fold(0, |mask, &(_, key)| mask | (1 << key))
+    }
+    
+    /// Format JSON : `{"dead_zone": 0.4, "keys": {"5": ["up", "ly-"], ...}}`
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let document = JsonValue::parse(text)?;
+        let keys = document.get("keys")
+            .and_then(JsonValue::as_object)
+            .ok_or("Le mapping manette doit avoir un objet \"keys\"")?;
+        
+        let mut map = GamepadMap::empty();
+        if let Some(dead_zone) = document.get("dead_zone") {
+            let dead_zone = dead_zone.as_f64().ok_or("\"dead_zone\" doit être un nombre")?;
+            map.set_dead_zone(dead_zone as f32);
+        }
+        
+        for (name, inputs) in keys {
+            let key = u8::from_str_radix(name, 16).ok()
+                .filter(|&key| key <= 0xF && name.len() == 1)
+                .ok_or_else(|| format!("Touche Chip-8 invalide: \"{}\"", name))?;
+            let inputs = match inputs {
+                JsonValue::Array(items) => items.as_slice(),
+                single => std::slice::from_ref(single),
+            };
+            for input in inputs {
+                let input = input.as_str()
+                    .and_then(GamepadInput::from_name)
+                    .ok_or_else(|| format!("Touche {:X}: entrée de manette inconnue {}", key, input))?;
+                map.bind(input, key);
+            }
+        }
+        Ok(map)
+    }
+    
+    /// Une ligne par touche Chip-8 associée, dans l'ordre 0-F
+    pub fn to_json(&self) -> String {
+        let lines: Vec<String> = (0..16u8)
+            .filter_map(|key| {
+                let inputs = self.inputs_for(key);
+                if inputs.is_empty() {
+                    return None;
+                }
+                let names: Vec<String> = inputs.into_iter().map(|input| json::quote(&input.name())).collect();
+                Some(format!("    \"{:X}\": [{}]", key, names.join(", ")))
+            })
+            .collect();
+        format!("{{\n  \"dead_zone\": {},\n  \"keys\": {{\n{}\n  }}\n}}\n", self.dead_zone, lines.join(",\n"))
+    }
+}
@@ -0,0 +1,85 @@
+//! Services fournis par l'environnement hôte (navigateur ou natif)
+//! Le cœur de l'émulateur ne dépend jamais directement de web-sys :
+//! les messages passent par un `Logger` interchangeable, et l'audio est
+//! déjà tiré par l'hôte via `Audio::fill_samples`
+
+use std::cell::RefCell;
+
+/// Destination des messages de diagnostic du cœur
+pub trait Logger {
+    fn log(&self, message: &str);
+}
+
+/// Ignore tous les messages (défaut en natif)
+pub struct NullLogger;
+
+impl Logger for NullLogger {
+    fn log(&self, _message: &str) {}
+}
+
+/// Écrit les messages sur la sortie d'erreur
+pub struct StderrLogger;
+
+impl Logger for StderrLogger {
+    fn log(&self, message: &str) {
+        eprintln!("{}", message);
+    }
+}
+
+/// Console du navigateur (défaut en WebAssembly)
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub struct ConsoleLogger;
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+impl Logger for ConsoleLogger {
+    fn log(&self, message: &str) {
+        web_sys::console::log_1(&message.into());
+    }
+}
+
+/// Manettes connectées au navigateur (mapping standard de la Gamepad API)
+#[cfg(feature = "gamepad")]
+pub fn gamepad_states() -> Vec<crate::GamepadState> {
+    use wasm_bindgen::JsCast;
+    
+    let Some(gamepads) = web_sys::window().and_then(|window| window.navigator().get_gamepads().ok()) else {
+        return Vec::new();
+    };
+    gamepads.iter()
+        .filter_map(|gamepad| gamepad.dyn_into::<web_sys::Gamepad>().ok())
+        .filter(|gamepad| gamepad.connected())
+        .map(|gamepad| crate::GamepadState {
+            buttons: gamepad.buttons().iter()
+                .map(|button| button.dyn_into::<web_sys::GamepadButton>().is_ok_and(|button| button.pressed()))
+                .collect(),
+            axes: gamepad.axes().iter()
+                .map(|axis| axis.as_f64().unwrap_or(0.0) as f32)
+                .collect(),
+        })
+        .collect()
+}
+
+fn default_logger() -> Box<dyn Logger> {
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    {
+        Box::new(ConsoleLogger)
+    }
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    {
+        Box::new(NullLogger)
+    }
+}
+
+thread_local! {
+    static LOGGER: RefCell<Box<dyn Logger>> = RefCell::new(default_logger());
+}
+
+/// Remplacer le logger du thread courant
+pub fn set_logger(logger: Box<dyn Logger>) {
+    LOGGER.with(|current| *current.borrow_mut() = logger);
+}
+
+/// Envoyer un message au logger courant
+pub fn log(message: &str) {
+    LOGGER.with(|logger| logger.borrow().log(message));
+}
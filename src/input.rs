@@ -1,40 +1,63 @@
 //! Clavier 16 touches hexadécimal
-//! Mapping: 1234 QWER ASDF ZXCV
+//! Mapping par défaut: 1234 QWER ASDF ZXCV
+//! Les appuis et relâchements de la frame courante sont mémorisés pour Fx0A
+//! `KeyMap` fait le lien entre les touches du clavier hôte et le pavé hexadécimal
+
+use crate::json::{self, JsonValue};
+use crate::savestate::{StateReader, StateWriter};
 
 pub struct Input {
     keys: [bool; 16],
-    last_key_pressed: Option<u8>,
+    /// Touches enfoncées depuis le début de la frame, bit n = touche n
+    pressed_edges: u16,
+    /// Touches relâchées depuis le début de la frame
+    released_edges: u16,
     waiting_for_key: bool,
 }
 
+impl Default for Input {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Input {
     pub fn new() -> Self {
         Input {
             keys: [false; 16],
-            last_key_pressed: None,
+            pressed_edges: 0,
+            released_edges: 0,
             waiting_for_key: false,
         }
     }
     
     pub fn clear(&mut self) {
         self.keys = [false; 16];
-        self.last_key_pressed = None;
+        self.pressed_edges = 0;
+        self.released_edges = 0;
         self.waiting_for_key = false;
     }
     
     pub fn key_down(&mut self, key: u8) {
-        if key < 16 {
+        if key < 16 && !self.keys[key as usize] {
             self.keys[key as usize] = true;
-            self.last_key_pressed = Some(key);
+            self.pressed_edges |= 1 << key;
         }
     }
     
     pub fn key_up(&mut self, key: u8) {
-        if key < 16 {
+        if key < 16 && self.keys[key as usize] {
             self.keys[key as usize] = false;
+            self.released_edges |= 1 << key;
         }
     }
     
+    /// Oublier les appuis et relâchements de la frame, à appeler à chaque tick 60 Hz
+    pub fn end_frame(&mut self) {
+        self.pressed_edges = 0;
+        self.released_edges = 0;
+    }
+    
     pub fn is_key_pressed(&self, key: u8) -> bool {
         if key < 16 {
             self.keys[key as usize]
@@ -43,14 +66,31 @@ impl Input {
         }
     }
     
-    pub fn get_key_pressed(&mut self) -> Option<u8> {
-        let key = self.last_key_pressed;
-        self.last_key_pressed = None;
-        key
+    /// Touches enfoncées, bit n = touche n
+    pub fn pressed_mask(&self) -> u16 {
+        self.keys.iter()
+            .enumerate()
+            .filter(|&(_, &pressed)| pressed)
+            .fold(0, |mask, (key, _)| mask | (1 << key))
     }
     
+    /// Consommer la plus petite touche relâchée (ou enfoncée si `on_press`) pendant la frame
+    pub fn take_key_event(&mut self, on_press: bool) -> Option<u8> {
+        let edges = if on_press { &mut self.pressed_edges } else { &mut self.released_edges };
+        if *edges == 0 {
+            return None;
+        }
+        let key = edges.trailing_zeros() as u8;
+        *edges &= !(1 << key);
+        Some(key)
+    }
+    
+    /// Début d'attente Fx0A : les touches relâchées avant l'instruction ne comptent pas
     pub fn wait_for_key(&mut self) {
-        self.waiting_for_key = true;
+        if !self.waiting_for_key {
+            self.waiting_for_key = true;
+            self.end_frame();
+        }
     }
     
     pub fn is_waiting_for_key(&self) -> bool {
@@ -68,41 +108,172 @@ impl Input {
                 state.push_str(&format!("{:X} ", i));
             }
         }
-        if let Some(last) = self.last_key_pressed {
-            state.push_str(&format!("| Last: {:X}", last));
+        if self.waiting_for_key {
+            state.push_str("| Fx0A");
         }
         state
     }
 }
 
 impl Input {
-    pub fn js_key_to_chip8(js_key: &str) -> Option<u8> {
-        match js_key {
-            "Digit1" | "1" => Some(0x1),
-            "Digit2" | "2" => Some(0x2),
-            "Digit3" | "3" => Some(0x3),
-            "Digit4" | "4" => Some(0xC),
-            
-            "KeyQ" | "q" => Some(0x4),
-            "KeyW" | "w" => Some(0x5),
-            "KeyE" | "e" => Some(0x6),
-            "KeyR" | "r" => Some(0xD),
-            
-            "KeyA" | "a" => Some(0x7),
-            "KeyS" | "s" => Some(0x8),
-            "KeyD" | "d" => Some(0x9),
-            "KeyF" | "f" => Some(0xE),
-            
-            "KeyZ" | "z" => Some(0xA),
-            "KeyX" | "x" => Some(0x0),
-            "KeyC" | "c" => Some(0xB),
-            "KeyV" | "v" => Some(0xF),
-            
+    pub fn chip8_key_name(key: u8) -> String {
+        format!("{:X}", key.min(15))
+    }
+}
+
+impl Input {
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        for &pressed in &self.keys {
+            writer.bool(pressed);
+        }
+        writer.u16(self.pressed_edges);
+        writer.u16(self.released_edges);
+        writer.bool(self.waiting_for_key);
+    }
+    
+    pub fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        for pressed in self.keys.iter_mut() {
+            *pressed = reader.bool()?;
+        }
+        self.pressed_edges = reader.u16()?;
+        self.released_edges = reader.u16()?;
+        self.waiting_for_key = reader.bool()?;
+        Ok(())
+    }
+}
+
+/// Pavé hexadécimal du COSMAC VIP, ligne par ligne
+const HEX_PAD: [u8; 16] = [
+    0x1, 0x2, 0x3, 0xC,
+    0x4, 0x5, 0x6, 0xD,
+    0x7, 0x8, 0x9, 0xE,
+    0xA, 0x0, 0xB, 0xF,
+];
+
+/// Bloc 1234/QWER/ASDF/ZXCV, repéré par position physique (`KeyboardEvent.code`)
+const LETTER_GRID: [&str; 16] = [
+    "Digit1", "Digit2", "Digit3", "Digit4",
+    "KeyQ", "KeyW", "KeyE", "KeyR",
+    "KeyA", "KeyS", "KeyD", "KeyF",
+    "KeyZ", "KeyX", "KeyC", "KeyV",
+];
+
+/// Pavé numérique : les chiffres gardent leur valeur, A-F sur les opérateurs
+const NUMPAD: [(&str, u8); 16] = [
+    ("Numpad0", 0x0), ("Numpad1", 0x1), ("Numpad2", 0x2), ("Numpad3", 0x3),
+    ("Numpad4", 0x4), ("Numpad5", 0x5), ("Numpad6", 0x6), ("Numpad7", 0x7),
+    ("Numpad8", 0x8), ("Numpad9", 0x9), ("NumpadDivide", 0xA), ("NumpadMultiply", 0xB),
+    ("NumpadSubtract", 0xC), ("NumpadAdd", 0xD), ("NumpadEnter", 0xE), ("NumpadDecimal", 0xF),
+];
+
+/// Correspondance touches de l'hôte -> touches Chip-8, plusieurs touches possibles par touche Chip-8
+/// Les touches sont des `KeyboardEvent.code` ("KeyQ", "Numpad8", "ArrowUp"...)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyMap {
+    bindings: Vec<(String, u8)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyMap {
+    /// Bloc de lettres à gauche du clavier
+    pub fn new() -> Self {
+        KeyMap {
+            bindings: LETTER_GRID.iter()
+                .zip(HEX_PAD)
+                .map(|(&code, key)| (String::from(code), key))
+                .collect(),
+        }
+    }
+    
+    /// Aucune touche associée
+    pub fn empty() -> Self {
+        KeyMap { bindings: Vec::new() }
+    }
+    
+    /// Presets "qwerty", "azerty", "qwertz" et "numpad"
+    /// `KeyboardEvent.code` désigne une position physique : les trois dispositions
+    /// utilisent le même bloc (1234/AZER/QSDF/WXCV en AZERTY, 1234/QWER/ASDF/YXCV en QWERTZ)
+    pub fn from_preset(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "qwerty" | "azerty" | "qwertz" | "default" => Some(Self::new()),
+            "numpad" | "keypad" => Some(KeyMap {
+                bindings: NUMPAD.iter().map(|&(code, key)| (String::from(code), key)).collect(),
+            }),
             _ => None,
         }
     }
     
-    pub fn chip8_key_name(key: u8) -> String {
-        format!("{:X}", key.min(15))
+    /// Touche Chip-8 associée à une touche de l'hôte
+    pub fn key_for(&self, code: &str) -> Option<u8> {
+        self.bindings.iter().find(|(bound, _)| bound == code).map(|&(_, key)| key)
+    }
+    
+    /// Touches de l'hôte associées à une touche Chip-8
+    pub fn codes_for(&self, key: u8) -> Vec<&str> {
+        self.bindings.iter()
+            .filter(|&&(_, bound)| bound == key)
+            .map(|(code, _)| code.as_str())
+            .collect()
     }
-}
\ No newline at end of file
+    
+    /// Associer une touche de l'hôte (remplace son ancienne association), false si `key` > F
+    pub fn bind(&mut self, code: &str, key: u8) -> bool {
+        if key > 0xF || code.is_empty() {
+            return false;
+        }
+        self.unbind(code);
+        self.bindings.push((String::from(code), key));
+        true
+    }
+    
+    /// Oublier une touche de l'hôte, false si elle n'était pas associée
+    pub fn unbind(&mut self, code: &str) -> bool {
+        let before = self.bindings.len();
+        self.bindings.retain(|(bound, _)| bound != code);
+        self.bindings.len() != before
+    }
+    
+    /// Oublier toutes les touches de l'hôte d'une touche Chip-8
+    pub fn clear_key(&mut self, key: u8) {
+        self.bindings.retain(|&(_, bound)| bound != key);
+    }
+    
+    /// Format JSON : `{"0": ["KeyX"], "1": ["Digit1", "Numpad1"], ...}`
+    /// (une chaîne seule est acceptée à la place d'un tableau)
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let document = JsonValue::parse(text)?;
+        let entries = document.as_object().ok_or("Le mapping doit être un objet JSON")?;
+        
+        let mut map = KeyMap::empty();
+        for (name, codes) in entries {
+            let key = u8::from_str_radix(name, 16).ok()
+                .filter(|&key| key <= 0xF && name.len() == 1)
+                .ok_or_else(|| format!("Touche Chip-8 invalide: \"{}\"", name))?;
+            let codes = match codes {
+                JsonValue::Array(items) => items.as_slice(),
+                single => std::slice::from_ref(single),
+            };
+            for code in codes {
+                let code = code.as_str().ok_or_else(|| format!("Touche {:X}: nom de touche attendu", key))?;
+                map.bind(code, key);
+            }
+        }
+        Ok(map)
+    }
+    
+    /// Une ligne par touche Chip-8, dans l'ordre 0-F
+    pub fn to_json(&self) -> String {
+        let lines: Vec<String> = (0..16u8)
+            .map(|key| {
+                let codes: Vec<String> = self.codes_for(key).into_iter().map(json::quote).collect();
+                format!("  \"{:X}\": [{}]", key, codes.join(", "))
+            })
+            .collect();
+        format!("{{\n{}\n}}\n", lines.join(",\n"))
+    }
+}
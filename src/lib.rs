@@ -1,112 +1,1101 @@
 //! Émulateur Chip-8 en Rust vers WebAssembly
+//! Les bindings JavaScript sont derrière la feature `wasm`, le cœur compile
+//! aussi en natif (`--no-default-features`)
 
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
-use console_error_panic_hook;
 
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
+pub mod host;
 mod cpu;
 mod memory;
 mod display;
 mod input;
 mod audio;
+mod quirks;
+mod savestate;
+mod rewind;
+mod rng;
+mod debugger;
+pub mod disasm;
+pub mod assembler;
+mod symbols;
+mod trace;
+mod error;
+mod policy;
+mod json;
+mod gamepad;
+mod movie;
+mod sha1;
+mod database;
 pub use cpu::Cpu;
 pub use memory::Memory;
 pub use display::Display;
-pub use input::Input;
+pub use input::{Input, KeyMap};
+pub use gamepad::{GamepadInput, GamepadMap, GamepadState};
 pub use audio::Audio;
+pub use quirks::{Quirks, Platform};
+pub use rewind::RewindBuffer;
+pub use rng::{Rng, RngKind};
+pub use debugger::{Debugger, BreakEvent, BreakKind, Register, Comparison, Condition};
+pub use memory::WatchKind;
+pub use symbols::SymbolMap;
+pub use trace::{Tracer, TraceEntry, Divergence};
+pub use error::{CpuError, CpuErrorKind, HaltReason};
+pub use policy::ExecutionPolicy;
+pub use movie::{Movie, MovieHeader, MovieEvent, MovieInput};
+use movie::{MovieRecorder, MoviePlayer};
+pub use sha1::sha1_hex;
+pub use database::{RomDatabase, RomInfo};
 
+#[cfg(feature = "wasm")]
 #[wasm_bindgen(start)]
 pub fn init() {
     console_error_panic_hook::set_once();
-    web_sys::console::log_1(&"Ferris-8 émulateur initialisé".into());
+    host::log("Ferris-8 émulateur initialisé");
 }
 
-#[wasm_bindgen]
+/// Garde-fou des step over / step out sur un sous-programme qui ne revient jamais
+const DEBUGGER_MAX_INSTRUCTIONS: u32 = 1_000_000;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn greet(name: &str) -> String {
     format!("Hello {}, bienvenue dans Ferris-8!", name)
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub struct Emulator {
     cpu: Cpu,
     running: bool,
+    rewind: RewindBuffer,
+    debugger: Debugger,
+    disasm_syntax: disasm::Syntax,
+    /// Exécution suspendue par un breakpoint ou un watchpoint
+    break_stop: bool,
+    key_map: KeyMap,
+    gamepad_map: GamepadMap,
+    /// Touches Chip-8 tenues par les manettes au dernier relevé
+    gamepad_keys: u16,
+    /// Dernière ROM chargée, relancée au début d'un film
+    rom: Vec<u8>,
+    recorder: Option<MovieRecorder>,
+    player: Option<MoviePlayer>,
+    database: RomDatabase,
+    use_database: bool,
+    /// Entrée de la base pour la ROM chargée
+    rom_info: Option<RomInfo>,
+    /// Réglages remplacés par ceux de la base, rendus au chargement suivant
+    rom_override: Option<RomOverride>,
 }
 
-#[wasm_bindgen]
+/// Réglages que la base de ROMs peut remplacer
+#[derive(Clone)]
+struct UserSettings {
+    platform: Platform,
+    quirks: Quirks,
+    stack_depth: usize,
+    key_map: KeyMap,
+    gamepad_map: GamepadMap,
+}
+
+/// Réglages de l'utilisateur mis de côté pendant une ROM connue de la base,
+/// avec ceux que la base leur a substitués
+struct RomOverride {
+    user: UserSettings,
+    applied: UserSettings,
+}
+
+impl Default for Emulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl Emulator {
-    #[wasm_bindgen(constructor)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
     pub fn new() -> Emulator {
-        web_sys::console::log_1(&"Création d'un nouvel émulateur".into());
+        host::log("Création d'un nouvel émulateur");
         
         Emulator {
             cpu: Cpu::new(),
             running: false,
+            rewind: RewindBuffer::new(),
+            debugger: Debugger::new(),
+            disasm_syntax: disasm::Syntax::default(),
+            break_stop: false,
+            key_map: KeyMap::new(),
+            gamepad_map: GamepadMap::new(),
+            gamepad_keys: 0,
+            rom: Vec::new(),
+            recorder: None,
+            player: None,
+            database: RomDatabase::bundled(),
+            use_database: true,
+            rom_info: None,
+            rom_override: None,
         }
     }
     
-    #[wasm_bindgen]
+    /// Charger une ROM, false sans rien changer si elle est vide ou dépasse la
+    /// mémoire de sa plateforme.
+    /// Une ROM connue de la base reçoit d'abord sa plateforme, ses quirks et ses
+    /// contrôles ; ceux de l'utilisateur reviennent avec la ROM suivante, sauf les
+    /// réglages qu'il a lui-même modifiés entre-temps, qui sont gardés
     pub fn load_rom(&mut self, rom_data: &[u8]) -> bool {
-        self.cpu.load_rom(rom_data);
+        let rom_info = if self.use_database { self.database.lookup(rom_data).cloned() } else { None };
+        let platform = rom_info.as_ref().map_or_else(|| self.user_platform(), |info| info.platform);
+        if rom_data.is_empty() || rom_data.len() > platform.max_rom_size() {
+            return false;
+        }
+        
+        self.rewind.clear();
+        self.debugger.reset();
+        self.break_stop = false;
+        self.gamepad_keys = 0;
+        self.cancel_movie();
+        self.cpu.symbols.clear();
+        
+        // Les réglages de la ROM précédente ne débordent pas sur celle-ci
+        self.restore_user_settings();
+        self.rom_info = rom_info;
+        if let Some(info) = &self.rom_info {
+            host::log(&format!("ROM reconnue: {} ({}, {} instructions par frame)",
+                info.title, info.platform.name(), info.cycles_per_frame));
+            let user = self.current_settings();
+            self.cpu.set_platform(info.platform);
+            self.cpu.quirks = info.quirks;
+            self.rom_override = Some(RomOverride { user, applied: self.current_settings() });
+            self.apply_rom_controls();
+        }
+        
+        let loaded = self.cpu.load_rom(rom_data);
+        debug_assert!(loaded, "ROM de {} octets vérifiée pour {}", rom_data.len(), platform.name());
+        self.rom = rom_data.to_vec();
         true
     }
     
-    #[wasm_bindgen]
+    /// Assembler un source Octo et le charger comme ROM, avec ses symboles de debug
+    /// (`file_name` apparaît dans les emplacements, ex. `game.8o:42`)
+    pub fn load_source(&mut self, source: &str, file_name: &str) -> Result<(), String> {
+        let program = assembler::assemble(source).map_err(|e| format!("{}:{}: {}", file_name, e.line, e.message))?;
+        if !self.load_rom(&program.rom) {
+            return Err(format!("Programme trop grand: {} octets", program.rom.len()));
+        }
+        self.cpu.symbols = SymbolMap::from_program(&program, file_name);
+        host::log(&format!("Programme assemblé: {} octets, {} labels", program.rom.len(), program.labels.len()));
+        Ok(())
+    }
+    
+    /// Charger une table de symboles pour la ROM courante (format de `export_symbols`)
+    pub fn load_symbols(&mut self, text: &str) -> Result<(), String> {
+        self.cpu.symbols = SymbolMap::parse(text)?;
+        Ok(())
+    }
+    
+    /// Table de symboles courante au format texte
+    pub fn export_symbols(&self) -> String {
+        self.cpu.symbols.to_text()
+    }
+    
+    pub fn clear_symbols(&mut self) {
+        self.cpu.symbols.clear();
+    }
+    
+    /// Exécuter une seule instruction, sans toucher aux timers
     pub fn cycle(&mut self) {
         if self.running {
             self.cpu.cycle();
         }
     }
     
-    #[wasm_bindgen]
+    /// Exécuter une frame de 1/60 s : N instructions puis un tick des timers
+    pub fn run_frame(&mut self, cycles_per_frame: u32) {
+        if self.running {
+            let cycles_per_frame = self.begin_movie_frame(cycles_per_frame);
+            if self.debugger.is_active(&self.cpu) {
+                let event = self.debugger.run_frame(&mut self.cpu, cycles_per_frame);
+                if matches!(event.kind, BreakKind::Breakpoint | BreakKind::WatchRead | BreakKind::WatchWrite) {
+                    host::log(&event.describe());
+                    self.running = false;
+                    self.break_stop = true;
+                }
+            } else {
+                self.cpu.run_frame(cycles_per_frame);
+            }
+            
+            if self.rewind.should_record() {
+                self.rewind.record(self.cpu.snapshot());
+            }
+            self.end_movie_frame();
+        }
+    }
+    
+    /// Décrémenter les timers à 60 Hz indépendamment des instructions
+    pub fn tick_timers(&mut self) {
+        if self.running {
+            self.cpu.tick_timers();
+        }
+    }
+    
     pub fn start(&mut self) {
         self.running = true;
+        self.break_stop = false;
     }
     
-    #[wasm_bindgen]
     pub fn stop(&mut self) {
         self.running = false;
     }
     
-    #[wasm_bindgen]
     pub fn reset(&mut self) {
         self.cpu.reset();
+        self.rewind.clear();
+        self.debugger.reset();
         self.running = false;
+        self.break_stop = false;
+        self.gamepad_keys = 0;
+        self.cancel_movie();
+    }
+    
+    pub fn get_display_buffer(&self) -> Vec<u8> {
+        self.cpu.get_display_buffer()
+    }
+    /// Index de couleur 0-3 par pixel (plan 1 = bit 0, plan 2 = bit 1)
+    pub fn get_plane_buffer(&self) -> Vec<u8> {
+        self.cpu.get_plane_buffer()
     }
     
-    #[wasm_bindgen]
-    pub fn get_display_buffer(&self) -> js_sys::Uint8Array {
-        let buffer = self.cpu.get_display_buffer();
-        js_sys::Uint8Array::from(&buffer[..])
+    /// Taille maximale de ROM pour la plateforme courante
+    pub fn max_rom_size(&self) -> usize {
+        self.cpu.memory.max_rom_size()
     }
-    #[wasm_bindgen]
+    
+    /// Largeur courante de l'écran (64 ou 128)
+    pub fn get_display_width(&self) -> usize {
+        self.cpu.display.width()
+    }
+    
+    /// Hauteur courante de l'écran (32 ou 64)
+    pub fn get_display_height(&self) -> usize {
+        self.cpu.display.height()
+    }
+    
+    /// Appuyer une touche Chip-8 ; ignoré pendant la relecture d'un film
     pub fn key_down(&mut self, key: u8) {
-        self.cpu.key_down(key);
+        self.input_event(MovieInput::KeyDown(key));
     }
     
-    #[wasm_bindgen]
     pub fn key_up(&mut self, key: u8) {
-        self.cpu.key_up(key);
+        self.input_event(MovieInput::KeyUp(key));
     }
     
-    #[wasm_bindgen]
     pub fn get_debug_info(&self) -> String {
         self.cpu.get_debug_info()
     }
     
-    #[wasm_bindgen]
     pub fn is_running(&self) -> bool {
         self.running && self.cpu.is_running()
     }
     
-    #[wasm_bindgen]
     pub fn get_stats(&self) -> String {
         self.cpu.get_stats()
     }
     
-    #[wasm_bindgen]
+    /// Fréquence d'échantillonnage de la sortie audio (AudioContext.sampleRate)
+    pub fn set_audio_sample_rate(&mut self, sample_rate: f32) {
+        self.cpu.audio.set_sample_rate(sample_rate);
+    }
+    
+    /// Nombre d'échantillons audio synthétisés en attente
+    pub fn audio_samples_available(&self) -> usize {
+        self.cpu.audio.samples_available()
+    }
+    
+    /// Remplir un Float32Array avec les échantillons PCM en attente (silence au-delà)
+    pub fn fill_audio_buffer(&mut self, out: &mut [f32]) -> usize {
+        self.cpu.audio.fill_samples(out)
+    }
+    
+    pub fn set_sound_enabled(&mut self, enabled: bool) {
+        self.cpu.audio.set_enabled(enabled);
+    }
+    
+    pub fn set_volume(&mut self, volume: f32) {
+        self.cpu.audio.set_volume(volume);
+    }
+    
+    /// Choisir le jeu d'instructions ("chip8", "schip", "xo-chip") et ses quirks recommandés
+    pub fn set_platform(&mut self, name: &str) -> bool {
+        match Platform::from_name(name) {
+            Some(platform) => {
+                self.cpu.set_platform(platform);
+                self.rewind.clear();
+                self.cancel_movie();
+                true
+            },
+            None => false,
+        }
+    }
+    
+    /// Appliquer un preset de quirks ("vip", "schip", "xo-chip")
+    pub fn set_quirks_preset(&mut self, name: &str) -> bool {
+        match Quirks::from_preset(name) {
+            Some(quirks) => {
+                self.cpu.quirks = quirks;
+                self.cancel_movie();
+                true
+            },
+            None => false,
+        }
+    }
+    
+    /// Graine du générateur de Cxkk, appliquée immédiatement et à chaque reset
+    pub fn set_seed(&mut self, seed: u64) {
+        self.cpu.rng.set_seed(seed);
+        self.cancel_movie();
+    }
+    
+    /// Choisir le générateur de Cxkk ("lcg", "xorshift", "vip")
+    pub fn set_rng(&mut self, name: &str) -> bool {
+        match RngKind::from_name(name) {
+            Some(kind) => {
+                self.cpu.rng.set_kind(kind);
+                self.cancel_movie();
+                true
+            },
+            None => false,
+        }
+    }
+    
+    /// Politique d'exécution ("accurate", "permissive", "strict"), règle aussi la protection des fonts
+    pub fn set_execution_policy(&mut self, name: &str) -> bool {
+        match ExecutionPolicy::from_name(name) {
+            Some(policy) => {
+                self.cpu.set_policy(policy);
+                self.cancel_movie();
+                true
+            },
+            None => false,
+        }
+    }
+    
+    /// Autoriser ou non la ROM à réécrire les fonts (0x50-0x1EF)
+    pub fn set_font_protection(&mut self, enabled: bool) {
+        self.cpu.memory.set_write_protection(enabled);
+        self.cancel_movie();
+    }
+    
+    /// Niveaux de sous-programmes (1-255) ; `set_platform` applique celui de la plateforme
+    pub fn set_stack_depth(&mut self, depth: usize) -> bool {
+        let changed = self.cpu.set_stack_depth(depth);
+        if changed {
+            self.cancel_movie();
+        }
+        changed
+    }
+    
+    pub fn get_stack_depth(&self) -> usize {
+        self.cpu.stack_depth()
+    }
+    
+    /// Plateforme courante ("chip8", "schip" ou "xo-chip")
+    pub fn get_platform(&self) -> String {
+        String::from(self.cpu.platform.name())
+    }
+    
+    /// Activer ou désactiver un quirk individuel
+    pub fn set_quirk(&mut self, name: &str, enabled: bool) -> bool {
+        let changed = self.cpu.quirks.set(name, enabled);
+        if changed {
+            self.cancel_movie();
+        }
+        changed
+    }
+    
     pub fn memory_dump(&self, start: u16, length: u16) -> String {
         self.cpu.memory_dump(start, length)
     }
-}
\ No newline at end of file
+    
+    /// Désassembler `count` instructions à partir de `start`
+    pub fn disassemble(&self, start: u16, count: usize) -> String {
+        self.cpu.disassemble(start, count, self.disasm_syntax)
+    }
+    
+    /// Syntaxe du désassembleur ("classic" ou "octo")
+    pub fn set_disassembly_syntax(&mut self, name: &str) -> bool {
+        match disasm::Syntax::from_name(name) {
+            Some(syntax) => {
+                self.disasm_syntax = syntax;
+                true
+            },
+            None => false,
+        }
+    }
+    
+    /// Registre PC courant, pour centrer le listing du debugger
+    pub fn get_pc(&self) -> u16 {
+        self.cpu.pc
+    }
+    
+    /// Sauvegarder l'état complet de la machine (format binaire versionné)
+    pub fn save_state(&self) -> Vec<u8> {
+        self.cpu.save_state()
+    }
+    
+    /// Restaurer une sauvegarde, l'état courant est conservé si elle est invalide
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), String> {
+        self.cpu.load_state(data)?;
+        self.rewind.clear();
+        self.debugger.reset();
+        self.break_stop = false;
+        self.gamepad_keys = 0;
+        self.cancel_movie();
+        Ok(())
+    }
+    
+    /// Activer le retour arrière : `capacity` photos prises toutes les
+    /// `interval` frames (capacity = 0 le désactive)
+    pub fn set_rewind(&mut self, capacity: usize, interval: u32) {
+        self.rewind.configure(capacity, interval);
+    }
+    
+    /// Revenir d'au moins `frames` frames en arrière, false si l'historique est vide
+    pub fn rewind(&mut self, frames: u32) -> bool {
+        let Some(state) = self.rewind.rewind(frames) else {
+            return false;
+        };
+        let restored = self.cpu.restore_snapshot(state).is_ok();
+        self.cancel_movie();
+        restored
+    }
+    
+    /// Nombre de frames disponibles dans l'historique de retour arrière
+    pub fn rewind_available_frames(&self) -> u32 {
+        self.rewind.available_frames()
+    }
+}
+
+/// Inspection : état de la machine en valeurs typées, sans texte à analyser
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Emulator {
+    /// V0 à VF
+    pub fn get_registers(&self) -> Vec<u8> {
+        self.cpu.v.to_vec()
+    }
+    
+    pub fn get_i(&self) -> u16 {
+        self.cpu.i
+    }
+    
+    pub fn get_sp(&self) -> u8 {
+        self.cpu.sp
+    }
+    
+    /// Adresses de retour empilées, de la plus ancienne à la plus récente
+    pub fn get_stack(&self) -> Vec<u16> {
+        self.cpu.call_stack().to_vec()
+    }
+    
+    /// Adresses de retour dans l'ordre d'une backtrace, la plus récente d'abord
+    pub fn call_stack(&self) -> Vec<u16> {
+        self.cpu.call_stack().iter().rev().copied().collect()
+    }
+    
+    /// Pile d'appels en clair, une ligne par niveau (`#0 main+0x6 (game.8o:3)`)
+    pub fn describe_call_stack(&self) -> String {
+        self.cpu.call_stack_text()
+    }
+    
+    pub fn get_delay_timer(&self) -> u8 {
+        self.cpu.delay_timer
+    }
+    
+    pub fn get_sound_timer(&self) -> u8 {
+        self.cpu.sound_timer
+    }
+    
+    /// Instructions exécutées depuis le chargement (BigInt côté JavaScript)
+    pub fn get_cycle_count(&self) -> u64 {
+        self.cpu.cycle_count
+    }
+    
+    pub fn get_error_count(&self) -> u32 {
+        self.cpu.error_count
+    }
+    
+    pub fn is_halted(&self) -> bool {
+        self.cpu.is_halted()
+    }
+    
+    /// Touches enfoncées, bit n = touche n
+    pub fn get_pressed_keys(&self) -> u16 {
+        self.cpu.input.pressed_mask()
+    }
+    
+    /// Copie d'une zone mémoire, tronquée à la fin de la RAM (sans déclencher les watchpoints)
+    pub fn get_memory(&self, start: u16, length: u16) -> Vec<u8> {
+        let end = (start as usize + length as usize).min(self.cpu.memory.size());
+        (start as usize..end).map(|address| self.cpu.memory.peek_byte(address as u16)).collect()
+    }
+}
+
+/// Clavier de l'hôte : correspondance avec le pavé hexadécimal
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Emulator {
+    /// Touche Chip-8 associée à un `KeyboardEvent.code`
+    pub fn map_key(&self, code: &str) -> Option<u8> {
+        self.key_map.key_for(code)
+    }
+    
+    /// Appliquer un preset ("qwerty", "azerty", "qwertz", "numpad")
+    pub fn set_key_map_preset(&mut self, name: &str) -> bool {
+        match KeyMap::from_preset(name) {
+            Some(key_map) => {
+                self.key_map = key_map;
+                true
+            },
+            None => false,
+        }
+    }
+    
+    /// Charger un mapping JSON (format de `export_key_map`), l'ancien est gardé en cas d'erreur
+    pub fn load_key_map(&mut self, json: &str) -> Result<(), String> {
+        self.key_map = KeyMap::from_json(json)?;
+        Ok(())
+    }
+    
+    pub fn export_key_map(&self) -> String {
+        self.key_map.to_json()
+    }
+    
+    /// Associer une touche du navigateur à une touche Chip-8, en plus des autres
+    pub fn bind_key(&mut self, code: &str, key: u8) -> bool {
+        self.key_map.bind(code, key)
+    }
+    
+    pub fn unbind_key(&mut self, code: &str) -> bool {
+        self.key_map.unbind(code)
+    }
+    
+    /// Touches du navigateur associées à une touche Chip-8
+    pub fn key_codes(&self, key: u8) -> Vec<String> {
+        self.key_map.codes_for(key).into_iter().map(String::from).collect()
+    }
+}
+
+/// Manettes : correspondance boutons et sticks -> touches Chip-8
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Emulator {
+    /// Appliquer un preset ("octo", "vip", "pong")
+    pub fn set_gamepad_preset(&mut self, name: &str) -> bool {
+        match GamepadMap::from_preset(name) {
+            Some(gamepad_map) => {
+                self.gamepad_map = gamepad_map;
+                true
+            },
+            None => false,
+        }
+    }
+    
+    /// Charger un mapping JSON (format de `export_gamepad_map`)
+    pub fn load_gamepad_map(&mut self, json: &str) -> Result<(), String> {
+        self.gamepad_map = GamepadMap::from_json(json)?;
+        Ok(())
+    }
+    
+    pub fn export_gamepad_map(&self) -> String {
+        self.gamepad_map.to_json()
+    }
+    
+    /// Associer un bouton ("a", "up", "button9") ou un demi-axe ("lx-", "ry+") à une touche
+    pub fn bind_gamepad(&mut self, input: &str, key: u8) -> bool {
+        GamepadInput::from_name(input).is_some_and(|input| self.gamepad_map.bind(input, key))
+    }
+    
+    pub fn unbind_gamepad(&mut self, input: &str) -> bool {
+        GamepadInput::from_name(input).is_some_and(|input| self.gamepad_map.unbind(input))
+    }
+    
+    /// Course minimale des sticks (0.05 à 0.95)
+    pub fn set_gamepad_dead_zone(&mut self, dead_zone: f32) {
+        self.gamepad_map.set_dead_zone(dead_zone);
+    }
+    
+    pub fn get_gamepad_dead_zone(&self) -> f32 {
+        self.gamepad_map.dead_zone()
+    }
+    
+    /// Touches tenues par les manettes, bit n = touche n
+    pub fn get_gamepad_keys(&self) -> u16 {
+        self.gamepad_keys
+    }
+}
+
+/// Relevé des manettes par la Gamepad API, à appeler une fois par frame
+#[cfg(feature = "gamepad")]
+#[wasm_bindgen]
+impl Emulator {
+    /// Nombre de manettes connectées
+    pub fn poll_gamepads(&mut self) -> usize {
+        let states = host::gamepad_states();
+        self.apply_gamepads(&states);
+        states.len()
+    }
+}
+
+/// Base de ROMs : réglages conseillés retrouvés par SHA-1 au chargement
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Emulator {
+    /// SHA-1 de la ROM chargée en hexadécimal, vide sans ROM
+    pub fn get_rom_sha1(&self) -> String {
+        if self.rom.is_empty() { String::new() } else { sha1_hex(&self.rom) }
+    }
+    
+    /// Titre de la ROM si elle est dans la base
+    pub fn get_rom_title(&self) -> Option<String> {
+        self.rom_info.as_ref().map(|info| info.title.clone())
+    }
+    
+    /// Instructions par frame conseillées, 0 si la ROM est inconnue
+    pub fn get_rom_cycles_per_frame(&self) -> u32 {
+        self.rom_info.as_ref().map_or(0, |info| info.cycles_per_frame)
+    }
+    
+    /// Couleurs conseillées en 0xRRGGBB par index de plan, vide si la ROM n'en a pas
+    pub fn get_rom_palette(&self) -> Vec<u32> {
+        self.rom_info.as_ref().map_or(Vec::new(), |info| info.palette.clone())
+    }
+    
+    /// Remettre les contrôles de la ROM sur le clavier et la manette,
+    /// false si elle n'en déclare pas
+    pub fn apply_rom_controls(&mut self) -> bool {
+        let Some(info) = &self.rom_info else {
+            return false;
+        };
+        if info.controls.is_empty() {
+            return false;
+        }
+        self.key_map = info.key_map(&self.key_map);
+        if let Some(map) = info.gamepad_map(self.gamepad_map.dead_zone()) {
+            self.gamepad_map = map;
+        }
+        if let Some(rom_override) = &mut self.rom_override {
+            rom_override.applied.key_map = self.key_map.clone();
+            rom_override.applied.gamepad_map = self.gamepad_map.clone();
+        }
+        true
+    }
+    
+    /// Appliquer ou non la base au chargement des ROMs (activée par défaut)
+    pub fn set_rom_database_enabled(&mut self, enabled: bool) {
+        self.use_database = enabled;
+    }
+    
+    /// Ajouter un `programs.json` de chip-8-database à la base,
+    /// retourne le nombre de ROMs lues
+    pub fn load_rom_database(&mut self, json: &str) -> Result<usize, String> {
+        let added = RomDatabase::from_json(json)?;
+        let count = added.len();
+        self.database.merge(added);
+        Ok(count)
+    }
+    
+    /// Nombre de ROMs connues
+    pub fn rom_database_size(&self) -> usize {
+        self.database.len()
+    }
+}
+
+/// Films : touches enregistrées frame par frame depuis le lancement de la ROM,
+/// relues à l'identique. Changer de ROM, de réglages ou d'état interrompt le film
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Emulator {
+    /// Relancer la ROM courante et enregistrer les touches à partir de maintenant
+    pub fn start_recording(&mut self) -> Result<(), String> {
+        if self.rom.is_empty() {
+            return Err(String::from("Aucune ROM à enregistrer"));
+        }
+        self.cancel_movie();
+        self.restart_rom();
+        self.recorder = Some(MovieRecorder::new(MovieHeader::capture(&self.cpu, &self.rom)));
+        host::log("Enregistrement du film démarré");
+        Ok(())
+    }
+    
+    /// Terminer l'enregistrement et retourner le film (vide si rien n'était enregistré)
+    pub fn stop_recording(&mut self) -> Vec<u8> {
+        match self.recorder.take() {
+            Some(recorder) => {
+                let movie = recorder.finish();
+                host::log(&format!("Film enregistré: {} frames, {} événements", movie.frames, movie.events.len()));
+                movie.to_bytes()
+            },
+            None => Vec::new(),
+        }
+    }
+    
+    /// Relancer la ROM avec les réglages du film et rejouer ses touches ;
+    /// le clavier et les manettes sont ignorés jusqu'à la fin du film
+    pub fn play_movie(&mut self, data: &[u8]) -> Result<(), String> {
+        let movie = Movie::from_bytes(data)?;
+        if !movie.header.matches_rom(&self.rom) {
+            return Err(format!("Film enregistré avec une autre ROM ({} octets, CRC {:08X})",
+                movie.header.rom_size, movie.header.rom_crc));
+        }
+        self.cancel_movie();
+        self.cpu.reset();
+        movie.header.apply(&mut self.cpu);
+        self.restart_rom();
+        host::log(&format!("Lecture du film: {} frames", movie.frames));
+        self.player = Some(MoviePlayer::new(movie));
+        Ok(())
+    }
+    
+    /// Arrêter la relecture, la partie continue avec les entrées de l'hôte
+    pub fn stop_movie(&mut self) {
+        self.player = None;
+    }
+    
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+    
+    pub fn is_playing_movie(&self) -> bool {
+        self.player.is_some()
+    }
+    
+    /// Frame courante du film enregistré ou relu
+    pub fn get_movie_frame(&self) -> u32 {
+        match (&self.recorder, &self.player) {
+            (Some(recorder), _) => recorder.frame(),
+            (_, Some(player)) => player.frame(),
+            _ => 0,
+        }
+    }
+    
+    /// Durée du film relu, en frames
+    pub fn get_movie_length(&self) -> u32 {
+        self.player.as_ref().map_or(0, MoviePlayer::length)
+    }
+}
+
+/// Erreurs d'exécution et raison d'arrêt
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Emulator {
+    /// Pourquoi l'exécution s'est arrêtée, None si rien ne l'a interrompue
+    pub fn get_halt_reason(&self) -> Option<HaltReason> {
+        self.cpu.halt_reason.or(self.break_stop.then_some(HaltReason::Breakpoint))
+    }
+    
+    /// Raison d'arrêt en clair, avec la dernière erreur si une erreur l'a provoqué
+    pub fn get_halt_message(&self) -> Option<String> {
+        let reason = self.get_halt_reason()?;
+        let mut message = String::from(reason.describe());
+        if matches!(reason, HaltReason::ErrorLimit | HaltReason::Trap)
+            && let Some(error) = self.cpu.errors().next_back()
+        {
+            message.push_str(&format!(" (dernière: {})", error.describe(&self.cpu.symbols)));
+        }
+        Some(message)
+    }
+    
+    /// Nature des dernières erreurs ("unknown_opcode", "invalid_jump"...),
+    /// de la plus ancienne à la plus récente
+    pub fn get_error_kinds(&self) -> Vec<String> {
+        self.cpu.errors().map(|error| String::from(error.kind.name())).collect()
+    }
+    
+    /// Adresse de l'instruction fautive de chaque erreur
+    pub fn get_error_pcs(&self) -> Vec<u16> {
+        self.cpu.errors().map(|error| error.pc).collect()
+    }
+    
+    pub fn get_error_opcodes(&self) -> Vec<u16> {
+        self.cpu.errors().map(|error| error.opcode).collect()
+    }
+    
+    /// Dernières erreurs en clair, une par ligne
+    pub fn describe_errors(&self) -> String {
+        self.cpu.errors()
+            .map(|error| format!("{}\n", error.describe(&self.cpu.symbols)))
+            .collect()
+    }
+    
+    pub fn clear_errors(&mut self) {
+        self.cpu.clear_errors();
+    }
+}
+
+/// Trace d'exécution des dernières instructions
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Emulator {
+    /// Garder les `capacity` dernières instructions (0 = désactivé)
+    pub fn set_trace(&mut self, capacity: usize) {
+        self.cpu.tracer.configure(capacity);
+    }
+    
+    pub fn trace_length(&self) -> usize {
+        self.cpu.tracer.len()
+    }
+    
+    /// Les `last` dernières instructions en texte (toutes si 0)
+    pub fn export_trace_text(&self, last: usize) -> String {
+        self.cpu.trace_text(last)
+    }
+    
+    /// Trace complète au format binaire compact
+    pub fn export_trace(&self) -> Vec<u8> {
+        self.cpu.tracer.to_binary()
+    }
+    
+    /// Comparer avec une trace de référence (texte ou binaire) :
+    /// description de la première divergence, None si les traces concordent
+    pub fn diff_trace(&self, reference: &[u8]) -> Result<Option<String>, String> {
+        let reference = Tracer::parse(reference)?;
+        Ok(self.cpu.tracer.diff(&reference)?.map(|divergence| divergence.describe()))
+    }
+}
+
+/// Debugger : breakpoints, watchpoints et pas à pas
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Emulator {
+    pub fn add_breakpoint(&mut self, address: u16) {
+        self.debugger.add_breakpoint(address, None);
+    }
+    
+    /// Breakpoint actif seulement si `register comparison value` est vrai,
+    /// ex: ("V3", ">=", 10) ; registres V0-VF, I, DT, ST
+    pub fn add_conditional_breakpoint(&mut self, address: u16, register: &str, comparison: &str, value: u16) -> bool {
+        let (Some(register), Some(comparison)) = (Register::from_name(register), Comparison::from_symbol(comparison)) else {
+            return false;
+        };
+        self.debugger.add_breakpoint(address, Some(Condition { register, comparison, value }));
+        true
+    }
+    
+    pub fn remove_breakpoint(&mut self, address: u16) -> bool {
+        self.debugger.remove_breakpoint(address)
+    }
+    
+    pub fn clear_breakpoints(&mut self) {
+        self.debugger.clear_breakpoints();
+    }
+    
+    /// Surveiller `length` octets à partir de `address` ("read", "write", "access")
+    pub fn add_watchpoint(&mut self, address: u16, length: u16, kind: &str) -> bool {
+        match WatchKind::from_name(kind) {
+            Some(kind) => {
+                self.cpu.memory.add_watchpoint(address, length, kind);
+                true
+            },
+            None => false,
+        }
+    }
+    
+    pub fn remove_watchpoint(&mut self, address: u16) -> bool {
+        self.cpu.memory.remove_watchpoint(address)
+    }
+    
+    pub fn clear_watchpoints(&mut self) {
+        self.cpu.memory.clear_watchpoints();
+    }
+    
+    /// Exécuter une seule instruction, même si l'émulateur est arrêté
+    pub fn step_instruction(&mut self) -> BreakEvent {
+        self.debugger.step_instruction(&mut self.cpu)
+    }
+    
+    /// Comme step_instruction, mais un 2nnn exécute tout le sous-programme
+    pub fn step_over(&mut self) -> BreakEvent {
+        self.debugger.step_over(&mut self.cpu, DEBUGGER_MAX_INSTRUCTIONS)
+    }
+    
+    /// Continuer jusqu'à la sortie (00EE) du sous-programme courant
+    pub fn step_out(&mut self) -> BreakEvent {
+        self.debugger.step_out(&mut self.cpu, DEBUGGER_MAX_INSTRUCTIONS)
+    }
+    
+    /// Exécuter au plus `max_instructions` instructions, jusqu'au prochain arrêt
+    pub fn run_until_break(&mut self, max_instructions: u32) -> BreakEvent {
+        self.debugger.run_until_break(&mut self.cpu, max_instructions)
+    }
+    
+    /// Dernier arrêt du debugger (breakpoint, watchpoint, pas à pas...)
+    pub fn last_break(&self) -> Option<BreakEvent> {
+        self.debugger.last_break()
+    }
+}
+
+/// API Rust uniquement (non exportée vers JavaScript)
+impl Emulator {
+    pub fn cpu(&self) -> &Cpu {
+        &self.cpu
+    }
+    
+    pub fn cpu_mut(&mut self) -> &mut Cpu {
+        &mut self.cpu
+    }
+    
+    /// Dernières erreurs d'exécution, de la plus ancienne à la plus récente
+    pub fn errors(&self) -> Vec<CpuError> {
+        self.cpu.errors().copied().collect()
+    }
+    
+    pub fn key_map(&self) -> &KeyMap {
+        &self.key_map
+    }
+    
+    pub fn set_key_map(&mut self, key_map: KeyMap) {
+        self.key_map = key_map;
+    }
+    
+    pub fn gamepad_map(&self) -> &GamepadMap {
+        &self.gamepad_map
+    }
+    
+    pub fn set_gamepad_map(&mut self, gamepad_map: GamepadMap) {
+        self.gamepad_map = gamepad_map;
+    }
+    
+    /// Entrée de la base pour la ROM chargée
+    pub fn rom_info(&self) -> Option<&RomInfo> {
+        self.rom_info.as_ref()
+    }
+    
+    pub fn rom_database(&self) -> &RomDatabase {
+        &self.database
+    }
+    
+    /// Remplacer la base (`RomDatabase::new()` pour repartir d'une base vide)
+    pub fn set_rom_database(&mut self, database: RomDatabase) {
+        self.database = database;
+    }
+    
+    /// État de toutes les manettes : seules les touches qui changent sont
+    /// appuyées ou relâchées, le clavier garde les siennes
+    pub fn apply_gamepads(&mut self, states: &[GamepadState]) {
+        let keys = states.iter().fold(0, |mask, state| mask | self.gamepad_map.pressed_keys(state));
+        let changed = keys ^ self.gamepad_keys;
+        for key in (0..16u8).filter(|&key| changed & (1 << key) != 0) {
+            if keys & (1 << key) != 0 {
+                self.key_down(key);
+            } else {
+                self.key_up(key);
+            }
+        }
+        self.gamepad_keys = keys;
+    }
+    
+    /// Touche de l'hôte : enregistrée si un film tourne, ignorée s'il est relu
+    fn input_event(&mut self, input: MovieInput) {
+        if self.player.is_some() {
+            return;
+        }
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(input);
+        }
+        self.apply_input(input);
+    }
+    
+    fn apply_input(&mut self, input: MovieInput) {
+        match input {
+            MovieInput::KeyDown(key) => self.cpu.key_down(key),
+            MovieInput::KeyUp(key) => self.cpu.key_up(key),
+            MovieInput::Cycles(_) => {},
+        }
+    }
+    
+    /// Touches du film pour la frame qui commence, et sa vitesse
+    fn begin_movie_frame(&mut self, cycles_per_frame: u32) -> u32 {
+        if let Some(recorder) = &mut self.recorder {
+            recorder.begin_frame(cycles_per_frame);
+        }
+        let Some(player) = &mut self.player else {
+            return cycles_per_frame;
+        };
+        let inputs = player.begin_frame();
+        let cycles = player.cycles(cycles_per_frame);
+        for input in inputs {
+            self.apply_input(input);
+        }
+        cycles
+    }
+    
+    fn end_movie_frame(&mut self) {
+        if let Some(recorder) = &mut self.recorder {
+            recorder.end_frame();
+        }
+        if let Some(player) = &mut self.player {
+            player.end_frame();
+            if player.is_finished() {
+                host::log(&format!("Fin du film ({} frames)", player.length()));
+                self.player = None;
+            }
+        }
+    }
+    
+    /// Abandonner le film en cours : la machine ne correspond plus à son en-tête
+    fn cancel_movie(&mut self) {
+        if self.recorder.take().is_some() {
+            host::log("Enregistrement du film abandonné");
+        }
+        if self.player.take().is_some() {
+            host::log("Lecture du film interrompue");
+        }
+    }
+    
+    /// Rendre à l'utilisateur les réglages que la base avait remplacés
+    fn current_settings(&self) -> UserSettings {
+        UserSettings {
+            platform: self.cpu.platform,
+            quirks: self.cpu.quirks,
+            stack_depth: self.cpu.stack_depth(),
+            key_map: self.key_map.clone(),
+            gamepad_map: self.gamepad_map.clone(),
+        }
+    }
+    
+    /// Plateforme qu'aura la machine une fois les réglages de l'utilisateur rendus
+    fn user_platform(&self) -> Platform {
+        match &self.rom_override {
+            Some(RomOverride { user, applied }) if self.cpu.platform == applied.platform => user.platform,
+            _ => self.cpu.platform,
+        }
+    }
+    
+    /// Rendre les réglages mis de côté par `load_rom`, sauf ceux que l'utilisateur
+    /// a changés depuis
+    fn restore_user_settings(&mut self) {
+        let Some(RomOverride { user, applied }) = self.rom_override.take() else {
+            return;
+        };
+        // `set_platform` remet quirks et pile à ses valeurs par défaut
+        let quirks = self.cpu.quirks;
+        let stack_depth = self.cpu.stack_depth();
+        if self.cpu.platform == applied.platform {
+            self.cpu.set_platform(user.platform);
+        }
+        self.cpu.quirks = if quirks == applied.quirks { user.quirks } else { quirks };
+        // Pas de `set_stack_depth` : la pile de la ROM précédente peut être plus profonde
+        self.cpu.resize_stack(if stack_depth == applied.stack_depth { user.stack_depth } else { stack_depth });
+        if self.key_map == applied.key_map {
+            self.key_map = user.key_map;
+        }
+        if self.gamepad_map == applied.gamepad_map {
+            self.gamepad_map = user.gamepad_map;
+        }
+    }
+    
+    /// Remettre la machine à l'allumage avec la ROM courante, réglages conservés
+    fn restart_rom(&mut self) {
+        self.cpu.reset();
+        self.cpu.load_rom(&self.rom);
+        self.rewind.clear();
+        self.debugger.reset();
+        self.break_stop = false;
+        self.gamepad_keys = 0;
+    }
+}
@@ -1,12 +1,18 @@
-//! Mémoire Chip-8 de 4KB
+//! Mémoire Chip-8 de 4KB (64KB en XO-CHIP)
 //! Zone réservée jusqu'à 0x1FF, programmes à partir de 0x200
 
-const MEMORY_SIZE: usize = 4096;
-const PROGRAM_START: usize = 0x200;
-const PROGRAM_END: usize = 0x1000;
+use std::cell::Cell;
+
+use crate::host::log;
+use crate::savestate::{StateReader, StateWriter};
+
+pub const MEMORY_SIZE: usize = 4096;
+pub const XO_MEMORY_SIZE: usize = 0x10000;
+pub(crate) const PROGRAM_START: usize = 0x200;
 const FONTSET_START: usize = 0x50;
 const FONTSET_SIZE: usize = 80;
-const MAX_ROM_SIZE: usize = PROGRAM_END - PROGRAM_START;
+const BIG_FONTSET_START: usize = FONTSET_START + FONTSET_SIZE;
+const BIG_FONTSET_SIZE: usize = 160;
 
 // Fontset hexadécimal 0-F
 const FONTSET: [u8; FONTSET_SIZE] = [
@@ -28,50 +34,141 @@ const FONTSET: [u8; FONTSET_SIZE] = [
     0xF0, 0x80, 0xF0, 0x80, 0x80  // F
 ];
 
+// Grand fontset SCHIP 8x10 (0-9, étendu à A-F comme Octo)
+const BIG_FONTSET: [u8; BIG_FONTSET_SIZE] = [
+    0xFF, 0xFF, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, // 0
+    0x18, 0x78, 0x78, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0xFF, // 1
+    0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // 2
+    0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 3
+    0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0x03, 0x03, // 4
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 5
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 6
+    0xFF, 0xFF, 0x03, 0x03, 0x06, 0x0C, 0x18, 0x18, 0x18, 0x18, // 7
+    0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 8
+    0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 9
+    0x7E, 0xFF, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xC3, // A
+    0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, // B
+    0x3C, 0xFF, 0xC3, 0xC0, 0xC0, 0xC0, 0xC0, 0xC3, 0xFF, 0x3C, // C
+    0xFC, 0xFE, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFE, 0xFC, // D
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // E
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xC0, 0xC0  // F
+];
+
+/// Type d'accès surveillé par un watchpoint
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchKind {
+    Read,
+    Write,
+    Access,
+}
+
+impl WatchKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "read" | "r" => Some(WatchKind::Read),
+            "write" | "w" => Some(WatchKind::Write),
+            "access" | "rw" => Some(WatchKind::Access),
+            _ => None,
+        }
+    }
+    
+    fn matches(self, write: bool) -> bool {
+        match self {
+            WatchKind::Read => !write,
+            WatchKind::Write => write,
+            WatchKind::Access => true,
+        }
+    }
+}
+
+/// Plage d'adresses surveillée [start, end)
+#[derive(Clone, Copy, Debug)]
+pub struct Watchpoint {
+    pub start: u16,
+    pub end: u32,
+    pub kind: WatchKind,
+}
+
+/// Premier accès surveillé depuis le dernier `take_watch_hit`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WatchHit {
+    pub address: u16,
+    pub value: u8,
+    pub write: bool,
+}
+
 pub struct Memory {
-    ram: [u8; MEMORY_SIZE],
+    ram: Vec<u8>,
     write_protected_zones: Vec<(usize, usize)>,
+    /// Protection des zones ci-dessus (désactivée pour les ROMs qui modifient les fonts)
+    write_protection: bool,
     access_count: u64,
+    
+    watchpoints: Vec<Watchpoint>,
+    /// Les lectures passent par &self : le déclenchement est noté dans une Cell
+    watch_hit: Cell<Option<WatchHit>>,
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Memory {
     pub fn new() -> Self {
         let mut memory = Memory {
-            ram: [0; MEMORY_SIZE],
+            ram: vec![0; MEMORY_SIZE],
             write_protected_zones: Vec::new(),
+            write_protection: true,
             access_count: 0,
+            
+            watchpoints: Vec::new(),
+            watch_hit: Cell::new(None),
         };
         
-        memory.write_protected_zones.push((FONTSET_START, FONTSET_START + FONTSET_SIZE));
+        memory.write_protected_zones.push((FONTSET_START, BIG_FONTSET_START + BIG_FONTSET_SIZE));
         memory
     }
     
     pub fn clear(&mut self) {
-        for i in PROGRAM_START..MEMORY_SIZE {
-            self.ram[i] = 0;
-        }
-        
-        for i in 0..FONTSET_START {
-            self.ram[i] = 0;
-        }
+        self.ram[PROGRAM_START..].fill(0);
+        self.ram[..FONTSET_START].fill(0);
         
         self.access_count = 0;
     }
     
+    /// Taille de la RAM adressable (4096 ou 65536 octets)
+    pub fn size(&self) -> usize {
+        self.ram.len()
+    }
+    
+    /// Redimensionner la RAM (XO-CHIP), le contenu au-delà de la nouvelle taille est perdu
+    pub fn resize(&mut self, size: usize) {
+        self.ram.resize(size, 0);
+    }
+    
+    /// Taille maximale d'une ROM chargée en 0x200
+    pub fn max_rom_size(&self) -> usize {
+        self.ram.len() - PROGRAM_START
+    }
+    
     pub fn load_fontset(&mut self) {
         for (i, &byte) in FONTSET.iter().enumerate() {
             self.ram[FONTSET_START + i] = byte;
         }
+        
+        for (i, &byte) in BIG_FONTSET.iter().enumerate() {
+            self.ram[BIG_FONTSET_START + i] = byte;
+        }
     }
     
     pub fn load_rom(&mut self, rom_data: &[u8]) -> bool {
-        if rom_data.is_empty() || rom_data.len() > MAX_ROM_SIZE {
+        if rom_data.is_empty() || rom_data.len() > self.max_rom_size() {
             return false;
         }
         
-        for i in PROGRAM_START..MEMORY_SIZE {
-            self.ram[i] = 0;
-        }
+        self.ram[PROGRAM_START..].fill(0);
         
         for (i, &byte) in rom_data.iter().enumerate() {
             self.ram[PROGRAM_START + i] = byte;
@@ -85,64 +182,74 @@ impl Memory {
     pub fn read_byte(&self, address: u16) -> u8 {
         let addr = address as usize;
         
-        if addr >= MEMORY_SIZE {
-            web_sys::console::log_1(
-                &format!(" Lecture hors limites: 0x{:04X} >= 0x{:04X}", address, MEMORY_SIZE).into()
-            );
+        if addr >= self.ram.len() {
+            log(&format!(" Lecture hors limites: 0x{:04X} >= 0x{:04X}", address, self.ram.len()));
             return 0;
         }
         
         // Statistiques d'accès
-        if addr >= PROGRAM_START && self.access_count % 10000 == 0 {
-            web_sys::console::log_1(&format!("{} accès mémoire", self.access_count).into());
+        if addr >= PROGRAM_START && self.access_count.is_multiple_of(10000) {
+            log(&format!("{} accès mémoire", self.access_count));
         }
         
+        self.check_watchpoints(address, self.ram[addr], false);
         self.ram[addr]
     }
     
+    /// Lire un byte sans log ni watchpoint (fetch, désassemblage, outils)
+    pub fn peek_byte(&self, address: u16) -> u8 {
+        self.ram.get(address as usize).copied().unwrap_or(0)
+    }
+    
     /// Écrire un byte à une adresse donnée avec protection
     pub fn write_byte(&mut self, address: u16, value: u8) {
         let addr = address as usize;
         
-        if addr >= MEMORY_SIZE {
-            web_sys::console::log_1(
-                &format!(" Écriture hors limites: 0x{:04X} >= 0x{:04X}", address, MEMORY_SIZE).into()
-            );
+        if addr >= self.ram.len() {
+            log(&format!(" Écriture hors limites: 0x{:04X} >= 0x{:04X}", address, self.ram.len()));
             return;
         }
         
+        self.check_watchpoints(address, value, true);
+        
         // Vérifier les zones protégées
-        for &(start, end) in &self.write_protected_zones {
-            if addr >= start && addr < end {
-                web_sys::console::log_1(
-                    &format!(" Tentative d'écriture en zone protégée: 0x{:04X} (zone 0x{:04X}-0x{:04X})", 
-                            address, start, end).into()
-                );
-                return;
-            }
+        if self.is_write_protected(address) {
+            log(&format!(" Tentative d'écriture en zone protégée: 0x{:04X}", address));
+            return;
         }
         
         // Avertissement si écriture dans zone système
-        if addr < PROGRAM_START && addr >= FONTSET_START + FONTSET_SIZE {
-            web_sys::console::log_1(
-                &format!(" Écriture en zone système: 0x{:04X}", address).into()
-            );
+        if (BIG_FONTSET_START + BIG_FONTSET_SIZE..PROGRAM_START).contains(&addr) {
+            log(&format!(" Écriture en zone système: 0x{:04X}", address));
         }
         
         self.ram[addr] = value;
         self.access_count += 1;
     }
     
+    /// Activer ou désactiver la protection en écriture des fonts
+    pub fn set_write_protection(&mut self, enabled: bool) {
+        self.write_protection = enabled;
+    }
+    
+    pub fn write_protection(&self) -> bool {
+        self.write_protection
+    }
+    
+    /// Une écriture à cette adresse serait-elle refusée ?
+    pub fn is_write_protected(&self, address: u16) -> bool {
+        let addr = address as usize;
+        self.write_protection && self.write_protected_zones.iter().any(|&(start, end)| addr >= start && addr < end)
+    }
+    
     /// Lire plusieurs bytes consécutifs avec validation
     pub fn read_bytes(&self, address: u16, count: u8) -> Vec<u8> {
         let mut result = Vec::with_capacity(count as usize);
         
         // Vérifier que la lecture complète est possible
-        if address as usize + count as usize > MEMORY_SIZE {
-            web_sys::console::log_1(
-                &format!(" Lecture multi-bytes hors limites: 0x{:04X}+{} > 0x{:04X}", 
-                        address, count, MEMORY_SIZE).into()
-            );
+        if address as usize + count as usize > self.ram.len() {
+            log(&format!(" Lecture multi-bytes hors limites: 0x{:04X}+{} > 0x{:04X}", 
+                        address, count, self.ram.len()));
             // Retourner des zéros pour éviter le crash
             return vec![0; count as usize];
         }
@@ -156,11 +263,9 @@ impl Memory {
     /// Écrire plusieurs bytes consécutifs avec validation
     pub fn write_bytes(&mut self, address: u16, data: &[u8]) -> bool {
         // Vérifier que l'écriture complète est possible
-        if address as usize + data.len() > MEMORY_SIZE {
-            web_sys::console::log_1(
-                &format!(" Écriture multi-bytes hors limites: 0x{:04X}+{} > 0x{:04X}", 
-                        address, data.len(), MEMORY_SIZE).into()
-            );
+        if address as usize + data.len() > self.ram.len() {
+            log(&format!(" Écriture multi-bytes hors limites: 0x{:04X}+{} > 0x{:04X}", 
+                        address, data.len(), self.ram.len()));
             return false;
         }
         
@@ -173,9 +278,7 @@ impl Memory {
     /// Obtenir l'adresse d'un caractère de font avec validation
     pub fn get_font_address(&self, character: u8) -> u16 {
         if character > 0xF {
-            web_sys::console::log_1(
-                &format!(" Caractère font invalide: 0x{:02X}, limité à 0-F", character).into()
-            );
+            log(&format!(" Caractère font invalide: 0x{:02X}, limité à 0-F", character));
             return FONTSET_START as u16; // Retourner '0' par défaut
         }
         
@@ -183,10 +286,16 @@ impl Memory {
         FONTSET_START as u16 + (character as u16 * 5)
     }
     
+    /// Adresse d'un caractère du grand fontset SCHIP (Fx30)
+    pub fn get_big_font_address(&self, character: u8) -> u16 {
+        // Chaque caractère fait 10 bytes
+        BIG_FONTSET_START as u16 + ((character & 0x0F) as u16 * 10)
+    }
+    
     /// Obtenir des statistiques de la mémoire
     pub fn get_stats(&self) -> String {
-        let program_bytes = self.count_non_zero_bytes(PROGRAM_START, MEMORY_SIZE);
-        let font_bytes = FONTSET_SIZE;
+        let program_bytes = self.count_non_zero_bytes(PROGRAM_START, self.ram.len());
+        let font_bytes = FONTSET_SIZE + BIG_FONTSET_SIZE;
         
         format!(
             "Mémoire: {}B programme, {}B fonts, {} accès total",
@@ -202,7 +311,7 @@ impl Memory {
     /// Dump hexadécimal d'une zone mémoire pour debug
     pub fn hex_dump(&self, start: u16, length: u16) -> String {
         let start_addr = start as usize;
-        let end_addr = (start as usize + length as usize).min(MEMORY_SIZE);
+        let end_addr = (start as usize + length as usize).min(self.ram.len());
         
         let mut dump = format!(" Dump mémoire 0x{:04X}-0x{:04X}:\n", start, end_addr - 1);
         
@@ -224,7 +333,7 @@ impl Memory {
             for i in 0..16 {
                 if addr + i < end_addr {
                     let byte = self.ram[addr + i];
-                    if byte >= 32 && byte <= 126 {
+                    if (32..=126).contains(&byte) {
                         dump.push(byte as char);
                     } else {
                         dump.push('.');
@@ -247,16 +356,22 @@ impl Memory {
         // Vérifier que les fonts sont intacts
         for (i, &expected) in FONTSET.iter().enumerate() {
             if self.ram[FONTSET_START + i] != expected {
-                web_sys::console::log_1(
-                    &format!(" Font corrompu à l'index {}: attendu 0x{:02X}, trouvé 0x{:02X}", 
-                            i, expected, self.ram[FONTSET_START + i]).into()
-                );
+                log(&format!(" Font corrompu à l'index {}: attendu 0x{:02X}, trouvé 0x{:02X}", 
+                            i, expected, self.ram[FONTSET_START + i]));
+                valid = false;
+            }
+        }
+        
+        for (i, &expected) in BIG_FONTSET.iter().enumerate() {
+            if self.ram[BIG_FONTSET_START + i] != expected {
+                log(&format!(" Grand font corrompu à l'index {}: attendu 0x{:02X}, trouvé 0x{:02X}", 
+                            i, expected, self.ram[BIG_FONTSET_START + i]));
                 valid = false;
             }
         }
         
         if valid {
-            web_sys::console::log_1(&"Intégrité mémoire vérifiée".into());
+            log("Intégrité mémoire vérifiée");
         }
         
         valid
@@ -266,13 +381,13 @@ impl Memory {
     pub fn get_address_info(&self, address: u16) -> String {
         let addr = address as usize;
         
-        if addr >= MEMORY_SIZE {
+        if addr >= self.ram.len() {
             return format!("0x{:04X}: HORS LIMITES", address);
         }
         
         let zone = if addr < FONTSET_START {
             "Système"
-        } else if addr < FONTSET_START + FONTSET_SIZE {
+        } else if addr < BIG_FONTSET_START + BIG_FONTSET_SIZE {
             "Fonts"
         } else if addr < PROGRAM_START {
             "Libre"
@@ -282,4 +397,65 @@ impl Memory {
         
         format!("0x{:04X}: {} = 0x{:02X}", address, zone, self.ram[addr])
     }
-}
\ No newline at end of file
+}
+
+/// Watchpoints du debugger
+impl Memory {
+    pub fn add_watchpoint(&mut self, start: u16, length: u16, kind: WatchKind) {
+        self.watchpoints.push(Watchpoint {
+            start,
+            end: start as u32 + length.max(1) as u32,
+            kind,
+        });
+    }
+    
+    /// Retirer les watchpoints qui commencent à `start`
+    pub fn remove_watchpoint(&mut self, start: u16) -> bool {
+        let before = self.watchpoints.len();
+        self.watchpoints.retain(|watch| watch.start != start);
+        self.watchpoints.len() != before
+    }
+    
+    pub fn clear_watchpoints(&mut self) {
+        self.watchpoints.clear();
+        self.watch_hit.set(None);
+    }
+    
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+    
+    /// Récupérer (et oublier) le premier accès surveillé
+    pub fn take_watch_hit(&self) -> Option<WatchHit> {
+        self.watch_hit.take()
+    }
+    
+    fn check_watchpoints(&self, address: u16, value: u8, write: bool) {
+        if self.watchpoints.is_empty() || self.watch_hit.get().is_some() {
+            return;
+        }
+        
+        let hit = self.watchpoints.iter().any(|watch| {
+            watch.kind.matches(write) && (watch.start as u32..watch.end).contains(&(address as u32))
+        });
+        if hit {
+            self.watch_hit.set(Some(WatchHit { address, value, write }));
+        }
+    }
+}
+
+impl Memory {
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        writer.vec(&self.ram);
+    }
+    
+    pub fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        let ram = reader.vec()?;
+        if ram.len() != MEMORY_SIZE && ram.len() != XO_MEMORY_SIZE {
+            return Err(format!("Taille de RAM invalide: {} octets", ram.len()));
+        }
+        
+        self.ram = ram;
+        Ok(())
+    }
+}
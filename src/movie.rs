@@ -0,0 +1,279 @@
+//! Films : touches enregistrées frame par frame et relecture à l'identique
+//! L'en-tête fige tout ce qui influence l'exécution (ROM, plateforme, quirks,
+//! générateur aléatoire, politique) ; le reste de la machine est déterministe,
+//! donc rejouer les mêmes touches aux mêmes frames redonne la même partie
+//!
+//! Format binaire little-endian : "F8MV", version, en-tête, nombre de frames,
+//! puis les événements dans l'ordre où ils ont été enregistrés
+
+use crate::{Cpu, ExecutionPolicy, Platform, Quirks, RngKind};
+use crate::savestate::{crc32, StateReader, StateWriter};
+
+const MAGIC: &[u8; 4] = b"F8MV";
+const MOVIE_VERSION: u16 = 1;
+
+/// Réglages de la machine au début du film
+#[derive(Clone, Debug, PartialEq)]
+pub struct MovieHeader {
+    /// CRC32 et taille de la ROM enregistrée
+    pub rom_crc: u32,
+    pub rom_size: u32,
+    pub platform: Platform,
+    pub quirks: Quirks,
+    pub rng: RngKind,
+    pub seed: u64,
+    pub policy: ExecutionPolicy,
+    pub font_protection: bool,
+    pub stack_depth: u8,
+    /// Flags RPL de SCHIP, qui survivent aux resets
+    pub rpl: [u8; 16],
+}
+
+impl MovieHeader {
+    /// Réglages courants du CPU, pour la ROM donnée
+    pub fn capture(cpu: &Cpu, rom: &[u8]) -> Self {
+        MovieHeader {
+            rom_crc: crc32(rom),
+            rom_size: rom.len() as u32,
+            platform: cpu.platform,
+            quirks: cpu.quirks,
+            rng: cpu.rng.kind(),
+            seed: cpu.rng.seed(),
+            policy: cpu.policy(),
+            font_protection: cpu.memory.write_protection(),
+            stack_depth: cpu.stack_depth() as u8,
+            rpl: cpu.rpl,
+        }
+    }
+    
+    /// Remettre le CPU dans ces réglages (à faire juste après son reset)
+    pub fn apply(&self, cpu: &mut Cpu) {
+        cpu.set_platform(self.platform);
+        cpu.quirks = self.quirks;
+        cpu.rng.set_kind(self.rng);
+        cpu.rng.set_seed(self.seed);
+        cpu.set_policy(self.policy);
+        cpu.memory.set_write_protection(self.font_protection);
+        cpu.set_stack_depth(self.stack_depth as usize);
+        cpu.rpl = self.rpl;
+    }
+    
+    pub fn matches_rom(&self, rom: &[u8]) -> bool {
+        self.rom_size == rom.len() as u32 && self.rom_crc == crc32(rom)
+    }
+}
+
+/// Entrée de l'hôte, appliquée au début d'une frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MovieInput {
+    KeyDown(u8),
+    KeyUp(u8),
+    /// Instructions par frame à partir de cette frame
+    Cycles(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MovieEvent {
+    pub frame: u32,
+    pub input: MovieInput,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Movie {
+    pub header: MovieHeader,
+    /// Durée du film en frames
+    pub frames: u32,
+    pub events: Vec<MovieEvent>,
+}
+
+impl Movie {
+    pub fn new(header: MovieHeader) -> Self {
+        Movie { header, frames: 0, events: Vec::new() }
+    }
+    
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header = &self.header;
+        let mut writer = StateWriter::new();
+        writer.bytes(MAGIC);
+        writer.u16(MOVIE_VERSION);
+        writer.u32(header.rom_crc);
+        writer.u32(header.rom_size);
+        writer.u8(header.platform.id());
+        header.quirks.save_state(&mut writer);
+        writer.u8(header.rng.id());
+        writer.u64(header.seed);
+        writer.u8(header.policy.id());
+        writer.bool(header.font_protection);
+        writer.u8(header.stack_depth);
+        writer.bytes(&header.rpl);
+        
+        writer.u32(self.frames);
+        writer.u32(self.events.len() as u32);
+        for event in &self.events {
+            writer.u32(event.frame);
+            match event.input {
+                MovieInput::KeyDown(key) => {
+                    writer.u8(0);
+                    writer.u32(key as u32);
+                },
+                MovieInput::KeyUp(key) => {
+                    writer.u8(1);
+                    writer.u32(key as u32);
+                },
+                MovieInput::Cycles(cycles) => {
+                    writer.u8(2);
+                    writer.u32(cycles);
+                },
+            }
+        }
+        writer.finish()
+    }
+    
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let mut reader = StateReader::new(data);
+        let mut magic = [0; 4];
+        reader.bytes(&mut magic).map_err(|_| String::from("Film invalide"))?;
+        if &magic != MAGIC {
+            return Err(String::from("Film invalide"));
+        }
+        let version = reader.u16()?;
+        if version != MOVIE_VERSION {
+            return Err(format!("Version de film non supportée: {}", version));
+        }
+        
+        let rom_crc = reader.u32()?;
+        let rom_size = reader.u32()?;
+        let platform = reader.u8()?;
+        let platform = Platform::from_id(platform).ok_or_else(|| format!("Plateforme inconnue: {}", platform))?;
+        let mut quirks = Quirks::default();
+        quirks.load_state(&mut reader)?;
+        let rng = reader.u8()?;
+        let rng = RngKind::from_id(rng).ok_or_else(|| format!("Générateur aléatoire inconnu: {}", rng))?;
+        let seed = reader.u64()?;
+        let policy = reader.u8()?;
+        let policy = ExecutionPolicy::from_id(policy).ok_or_else(|| format!("Politique d'exécution inconnue: {}", policy))?;
+        let font_protection = reader.bool()?;
+        let stack_depth = reader.u8()?;
+        if stack_depth == 0 {
+            return Err(String::from("Profondeur de pile invalide: 0"));
+        }
+        let mut rpl = [0; 16];
+        reader.bytes(&mut rpl)?;
+        let header = MovieHeader { rom_crc, rom_size, platform, quirks, rng, seed, policy, font_protection, stack_depth, rpl };
+        
+        let frames = reader.u32()?;
+        let count = reader.u32()? as usize;
+        let mut events = Vec::with_capacity(count.min(data.len()));
+        for _ in 0..count {
+            let frame = reader.u32()?;
+            let kind = reader.u8()?;
+            let value = reader.u32()?;
+            let input = match kind {
+                0 | 1 if value > 0xF => return Err(format!("Touche invalide frame {}: {}", frame, value)),
+                0 => MovieInput::KeyDown(value as u8),
+                1 => MovieInput::KeyUp(value as u8),
+                2 => MovieInput::Cycles(value),
+                _ => return Err(format!("Événement inconnu frame {}: {}", frame, kind)),
+            };
+            if frame >= frames || events.last().is_some_and(|last: &MovieEvent| last.frame > frame) {
+                return Err(format!("Événement hors séquence frame {}", frame));
+            }
+            events.push(MovieEvent { frame, input });
+        }
+        reader.finish()?;
+        
+        Ok(Movie { header, frames, events })
+    }
+}
+
+/// Film en cours d'enregistrement
+#[derive(Clone, Debug)]
+pub struct MovieRecorder {
+    movie: Movie,
+    cycles: Option<u32>,
+}
+
+impl MovieRecorder {
+    pub fn new(header: MovieHeader) -> Self {
+        MovieRecorder { movie: Movie::new(header), cycles: None }
+    }
+    
+    /// Frame en cours, comptée depuis le début de l'enregistrement
+    pub fn frame(&self) -> u32 {
+        self.movie.frames
+    }
+    
+    /// Entrée reçue avant la frame en cours
+    pub fn record(&mut self, input: MovieInput) {
+        self.movie.events.push(MovieEvent { frame: self.movie.frames, input });
+    }
+    
+    /// Une frame de `cycles` instructions va s'exécuter
+    pub fn begin_frame(&mut self, cycles: u32) {
+        if self.cycles != Some(cycles) {
+            self.cycles = Some(cycles);
+            self.record(MovieInput::Cycles(cycles));
+        }
+    }
+    
+    pub fn end_frame(&mut self) {
+        self.movie.frames += 1;
+    }
+    
+    /// Film terminé ; les touches reçues après la dernière frame n'ont plus d'effet
+    pub fn finish(mut self) -> Movie {
+        let frames = self.movie.frames;
+        self.movie.events.retain(|event| event.frame < frames);
+        self.movie
+    }
+}
+
+/// Film en cours de relecture
+#[derive(Clone, Debug)]
+pub struct MoviePlayer {
+    movie: Movie,
+    frame: u32,
+    next: usize,
+    cycles: Option<u32>,
+}
+
+impl MoviePlayer {
+    pub fn new(movie: Movie) -> Self {
+        MoviePlayer { movie, frame: 0, next: 0, cycles: None }
+    }
+    
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+    
+    pub fn length(&self) -> u32 {
+        self.movie.frames
+    }
+    
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.movie.frames
+    }
+    
+    /// Touches à appliquer avant la frame en cours ; les changements de vitesse
+    /// sont retenus pour `cycles`
+    pub fn begin_frame(&mut self) -> Vec<MovieInput> {
+        let mut inputs = Vec::new();
+        while let Some(event) = self.movie.events.get(self.next).filter(|event| event.frame == self.frame) {
+            match event.input {
+                MovieInput::Cycles(cycles) => self.cycles = Some(cycles),
+                input => inputs.push(input),
+            }
+            self.next += 1;
+        }
+        inputs
+    }
+    
+    /// Instructions par frame enregistrées, `default` si le film n'en donne pas
+    pub fn cycles(&self, default: u32) -> u32 {
+        self.cycles.unwrap_or(default)
+    }
+    
+    pub fn end_frame(&mut self) {
+        self.frame += 1;
+    }
+}
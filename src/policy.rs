@@ -0,0 +1,49 @@
+//! Politique d'exécution : que faire d'un PC, d'un saut ou d'un accès
+//! mémoire que le matériel accepterait mais qui trahit souvent un bug
+
+/// Réaction du CPU aux adresses hors programme et aux écritures dans les fonts
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ExecutionPolicy {
+    /// Comme le matériel : les adresses bouclent sur la mémoire, PC impair
+    /// et sauts sous 0x200 autorisés, les fonts sont modifiables
+    Accurate,
+    /// Récupération : PC ramené en 0x200 ou aligné, sauts invalides ignorés,
+    /// chaque anomalie compte comme une erreur
+    #[default]
+    Permissive,
+    /// La première anomalie arrête le CPU avec une erreur typée
+    Strict,
+}
+
+impl ExecutionPolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "accurate" | "hardware" => Some(ExecutionPolicy::Accurate),
+            "permissive" | "default" => Some(ExecutionPolicy::Permissive),
+            "strict" => Some(ExecutionPolicy::Strict),
+            _ => None,
+        }
+    }
+    
+    pub fn id(self) -> u8 {
+        match self {
+            ExecutionPolicy::Accurate => 0,
+            ExecutionPolicy::Permissive => 1,
+            ExecutionPolicy::Strict => 2,
+        }
+    }
+    
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(ExecutionPolicy::Accurate),
+            1 => Some(ExecutionPolicy::Permissive),
+            2 => Some(ExecutionPolicy::Strict),
+            _ => None,
+        }
+    }
+    
+    /// Les fonts sont-elles protégées en écriture par défaut ?
+    pub fn protects_fonts(self) -> bool {
+        self != ExecutionPolicy::Accurate
+    }
+}
@@ -0,0 +1,218 @@
+//! Quirks de compatibilité Chip-8
+//! Chaque variante (COSMAC VIP, CHIP-48/SCHIP, XO-CHIP) interprète
+//! certains opcodes différemment : on les rend configurables
+
+use crate::memory::{MEMORY_SIZE, PROGRAM_START, XO_MEMORY_SIZE};
+use crate::savestate::{StateReader, StateWriter};
+
+/// Jeu d'instructions émulé
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Platform {
+    /// Chip-8 original : 35 opcodes, écran 64x32
+    #[default]
+    Chip8,
+    /// SUPER-CHIP 1.1 : 128x64, scrolling, sprites 16x16, flags RPL
+    SuperChip,
+    /// XO-CHIP : extensions SCHIP, 64KB de RAM, deux bitplanes
+    XoChip,
+}
+
+impl Platform {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "chip8" | "chip-8" | "vip" => Some(Platform::Chip8),
+            "schip" | "superchip" | "super-chip" => Some(Platform::SuperChip),
+            "xo-chip" | "xochip" | "xo" => Some(Platform::XoChip),
+            _ => None,
+        }
+    }
+    
+    /// Nom court accepté par `from_name`
+    pub fn name(self) -> &'static str {
+        match self {
+            Platform::Chip8 => "chip8",
+            Platform::SuperChip => "schip",
+            Platform::XoChip => "xo-chip",
+        }
+    }
+    
+    /// Identifiant stable utilisé dans les sauvegardes
+    pub fn id(self) -> u8 {
+        match self {
+            Platform::Chip8 => 0,
+            Platform::SuperChip => 1,
+            Platform::XoChip => 2,
+        }
+    }
+    
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Platform::Chip8),
+            1 => Some(Platform::SuperChip),
+            2 => Some(Platform::XoChip),
+            _ => None,
+        }
+    }
+    
+    /// Quirks recommandés pour cette plateforme
+    pub fn default_quirks(self) -> Quirks {
+        match self {
+            Platform::Chip8 => Quirks::vip(),
+            Platform::SuperChip => Quirks::schip(),
+            Platform::XoChip => Quirks::xo_chip(),
+        }
+    }
+    
+    /// Niveaux de sous-programmes : 12 sur le COSMAC VIP, 16 sur la HP48,
+    /// davantage pour les programmes Octo récursifs
+    pub fn stack_depth(self) -> usize {
+        match self {
+            Platform::Chip8 => 12,
+            Platform::SuperChip => 16,
+            Platform::XoChip => 64,
+        }
+    }
+    
+    /// RAM adressable : 4KB, 64KB en XO-CHIP
+    pub fn memory_size(self) -> usize {
+        if self.has_xo_opcodes() { XO_MEMORY_SIZE } else { MEMORY_SIZE }
+    }
+    
+    /// Taille maximale d'une ROM chargée en 0x200
+    pub fn max_rom_size(self) -> usize {
+        self.memory_size() - PROGRAM_START
+    }
+    
+    /// Les opcodes SCHIP (00Cn, 00FB-00FF, Dxy0, Fx30, Fx75, Fx85) sont-ils reconnus ?
+    pub fn has_schip_opcodes(self) -> bool {
+        self != Platform::Chip8
+    }
+    
+    /// Les opcodes XO-CHIP (00Dn, 5xy2, 5xy3, F000, Fn01) sont-ils reconnus ?
+    pub fn has_xo_opcodes(self) -> bool {
+        self == Platform::XoChip
+    }
+}
+
+/// Comportements optionnels consultés par le CPU et l'affichage
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quirks {
+    /// 8xy6/8xyE décalent Vx en place et ignorent Vy (CHIP-48)
+    pub shift: bool,
+    /// Fx55/Fx65 incrémentent I de x + 1 (COSMAC VIP)
+    pub memory_increment: bool,
+    /// Bnnn saute à Vx + nnn au lieu de V0 + nnn (CHIP-48)
+    pub jump_vx: bool,
+    /// 8xy1/8xy2/8xy3 remettent VF à 0 (COSMAC VIP)
+    pub vf_reset: bool,
+    /// Les sprites sont coupés au bord de l'écran au lieu de boucler
+    pub clip: bool,
+    /// Dxyn attend le prochain rafraîchissement (60 Hz) avant de dessiner
+    pub display_wait: bool,
+    /// Fx0A rend la main dès l'appui d'une touche au lieu d'attendre son relâchement
+    pub key_press: bool,
+}
+
+impl Quirks {
+    /// Interpréteur original du COSMAC VIP
+    pub const fn vip() -> Self {
+        Quirks {
+            shift: false,
+            memory_increment: true,
+            jump_vx: false,
+            vf_reset: true,
+            clip: true,
+            display_wait: true,
+            key_press: false,
+        }
+    }
+    
+    /// SUPER-CHIP 1.1 (HP48)
+    pub const fn schip() -> Self {
+        Quirks {
+            shift: true,
+            memory_increment: false,
+            jump_vx: true,
+            vf_reset: false,
+            clip: true,
+            display_wait: false,
+            key_press: false,
+        }
+    }
+    
+    /// XO-CHIP (Octo)
+    pub const fn xo_chip() -> Self {
+        Quirks {
+            shift: false,
+            memory_increment: true,
+            jump_vx: false,
+            vf_reset: false,
+            clip: false,
+            display_wait: false,
+            key_press: false,
+        }
+    }
+    
+    /// Retrouver un preset par son nom ("vip", "schip", "xo-chip")
+    pub fn from_preset(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "vip" | "chip8" | "chip-8" | "cosmac" => Some(Self::vip()),
+            "schip" | "superchip" | "super-chip" | "chip48" | "chip-48" => Some(Self::schip()),
+            "xo-chip" | "xochip" | "xo" | "octo" => Some(Self::xo_chip()),
+            "ferris8" | "default" => Some(Self::default()),
+            _ => None,
+        }
+    }
+    
+    /// Activer ou désactiver un quirk par son nom, false si le nom est inconnu
+    pub fn set(&mut self, name: &str, enabled: bool) -> bool {
+        let quirk = match name {
+            "shift" => &mut self.shift,
+            "memory_increment" | "load_store" => &mut self.memory_increment,
+            "jump_vx" | "jump" => &mut self.jump_vx,
+            "vf_reset" => &mut self.vf_reset,
+            "clip" => &mut self.clip,
+            "display_wait" => &mut self.display_wait,
+            "key_press" => &mut self.key_press,
+            _ => return false,
+        };
+        *quirk = enabled;
+        true
+    }
+    
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        writer.bool(self.shift);
+        writer.bool(self.memory_increment);
+        writer.bool(self.jump_vx);
+        writer.bool(self.vf_reset);
+        writer.bool(self.clip);
+        writer.bool(self.display_wait);
+        writer.bool(self.key_press);
+    }
+    
+    pub fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        self.shift = reader.bool()?;
+        self.memory_increment = reader.bool()?;
+        self.jump_vx = reader.bool()?;
+        self.vf_reset = reader.bool()?;
+        self.clip = reader.bool()?;
+        self.display_wait = reader.bool()?;
+        self.key_press = reader.bool()?;
+        Ok(())
+    }
+}
+
+impl Default for Quirks {
+    /// Comportement historique de Ferris-8 : shift CHIP-48, sprites qui bouclent
+    fn default() -> Self {
+        Quirks {
+            shift: true,
+            memory_increment: false,
+            jump_vx: false,
+            vf_reset: false,
+            clip: false,
+            display_wait: false,
+            key_press: false,
+        }
+    }
+}
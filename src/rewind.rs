@@ -0,0 +1,180 @@
+//! Retour arrière : historique circulaire d'états de la machine
+//! Seul le dernier état est gardé en entier, les précédents sont stockés
+//! comme deltas (XOR puis RLE des zéros) qui permettent de remonter le temps.
+//! La RAM change peu d'une frame à l'autre, un delta fait quelques dizaines d'octets
+
+use std::collections::VecDeque;
+
+pub struct RewindBuffer {
+    /// Nombre maximal de deltas conservés, 0 = désactivé
+    capacity: usize,
+    /// Une photo toutes les `interval` frames
+    interval: u32,
+    frames_since_snapshot: u32,
+    /// Dernier état enregistré, en clair
+    latest: Vec<u8>,
+    /// Deltas du plus ancien au plus récent : chacun ramène à l'état précédent
+    deltas: VecDeque<Vec<u8>>,
+}
+
+impl Default for RewindBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RewindBuffer {
+    pub fn new() -> Self {
+        RewindBuffer {
+            capacity: 0,
+            interval: 1,
+            frames_since_snapshot: 0,
+            latest: Vec::new(),
+            deltas: VecDeque::new(),
+        }
+    }
+    
+    /// Configurer la profondeur de l'historique, vide ce qui était enregistré
+    pub fn configure(&mut self, capacity: usize, interval: u32) {
+        self.capacity = capacity;
+        self.interval = interval.max(1);
+        self.clear();
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+    
+    pub fn clear(&mut self) {
+        self.frames_since_snapshot = 0;
+        self.latest.clear();
+        self.deltas.clear();
+    }
+    
+    /// Nombre de frames que l'on peut encore remonter
+    pub fn available_frames(&self) -> u32 {
+        self.deltas.len() as u32 * self.interval
+    }
+    
+    /// Taille mémoire occupée par l'historique
+    pub fn memory_usage(&self) -> usize {
+        self.latest.len() + self.deltas.iter().map(Vec::len).sum::<usize>()
+    }
+    
+    /// Appelé à chaque frame : la photo n'est prise que toutes les `interval` frames
+    pub fn should_record(&mut self) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        
+        self.frames_since_snapshot += 1;
+        if self.frames_since_snapshot < self.interval && !self.latest.is_empty() {
+            return false;
+        }
+        self.frames_since_snapshot = 0;
+        true
+    }
+    
+    /// Enregistrer un nouvel état, le précédent devient un delta
+    pub fn record(&mut self, state: Vec<u8>) {
+        if !self.latest.is_empty() {
+            self.deltas.push_back(encode_delta(&state, &self.latest));
+            while self.deltas.len() > self.capacity {
+                self.deltas.pop_front();
+            }
+        }
+        self.latest = state;
+    }
+    
+    /// Remonter d'au moins `frames` frames, retourne l'état atteint
+    /// (None si l'historique est vide)
+    pub fn rewind(&mut self, frames: u32) -> Option<&[u8]> {
+        if self.deltas.is_empty() {
+            return None;
+        }
+        
+        let steps = frames.div_ceil(self.interval).max(1) as usize;
+        for _ in 0..steps.min(self.deltas.len()) {
+            if let Some(delta) = self.deltas.pop_back() {
+                self.latest = apply_delta(&self.latest, &delta);
+            }
+        }
+        
+        self.frames_since_snapshot = 0;
+        Some(&self.latest)
+    }
+}
+
+/// Delta permettant de retrouver `previous` à partir de `current` :
+/// longueur de `previous`, puis des paires (zéros à sauter, octets XOR littéraux)
+fn encode_delta(current: &[u8], previous: &[u8]) -> Vec<u8> {
+    let length = current.len().max(previous.len());
+    let xor = |index: usize| {
+        current.get(index).copied().unwrap_or(0) ^ previous.get(index).copied().unwrap_or(0)
+    };
+    
+    let mut delta = Vec::new();
+    write_varint(&mut delta, previous.len());
+    
+    let mut index = 0;
+    while index < length {
+        let zeros_start = index;
+        while index < length && xor(index) == 0 {
+            index += 1;
+        }
+        let literal_start = index;
+        while index < length && xor(index) != 0 {
+            index += 1;
+        }
+        
+        write_varint(&mut delta, literal_start - zeros_start);
+        write_varint(&mut delta, index - literal_start);
+        delta.extend((literal_start..index).map(xor));
+    }
+    delta
+}
+
+fn apply_delta(current: &[u8], delta: &[u8]) -> Vec<u8> {
+    let mut position = 0;
+    let previous_length = read_varint(delta, &mut position);
+    
+    let mut state = current.to_vec();
+    state.resize(current.len().max(previous_length), 0);
+    
+    let mut index = 0;
+    while position < delta.len() {
+        index += read_varint(delta, &mut position);
+        let literals = read_varint(delta, &mut position);
+        for byte in &delta[position..position + literals] {
+            state[index] ^= byte;
+            index += 1;
+        }
+        position += literals;
+    }
+    
+    state.truncate(previous_length);
+    state
+}
+
+/// Entier de taille variable, 7 bits par octet
+fn write_varint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(data: &[u8], position: &mut usize) -> usize {
+    let mut value = 0;
+    let mut shift = 0;
+    while let Some(&byte) = data.get(*position) {
+        *position += 1;
+        value |= ((byte & 0x7F) as usize) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    value
+}
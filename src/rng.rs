@@ -0,0 +1,150 @@
+//! Générateur aléatoire de Cxkk
+//! L'état vit dans le CPU : il est remis à la graine à chaque reset et fait
+//! partie des sauvegardes, pour que deux exécutions identiques donnent les mêmes tirages
+
+use crate::Memory;
+use crate::savestate::{StateReader, StateWriter};
+
+/// Graine historique de Ferris-8
+pub const DEFAULT_SEED: u64 = 12345;
+
+/// Algorithme de tirage
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum RngKind {
+    /// Congruentiel linéaire (constantes de la libc), générateur d'origine de Ferris-8
+    #[default]
+    Lcg,
+    /// xorshift64* : bien meilleure distribution, même coût
+    Xorshift,
+    /// Schéma du COSMAC VIP : un compteur avance à chaque interruption 60 Hz et
+    /// à chaque tirage, et on additionne l'octet de la page 0 (polices) lu à ce
+    /// compteur, comme le VIP qui lisait son propre code d'interpréteur.
+    /// Les tirages dépendent donc du timing du programme, comme sur la machine
+    Vip,
+}
+
+impl RngKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "lcg" | "default" => Some(RngKind::Lcg),
+            "xorshift" | "xorshift64" => Some(RngKind::Xorshift),
+            "vip" | "cosmac" => Some(RngKind::Vip),
+            _ => None,
+        }
+    }
+    
+    pub fn id(self) -> u8 {
+        match self {
+            RngKind::Lcg => 0,
+            RngKind::Xorshift => 1,
+            RngKind::Vip => 2,
+        }
+    }
+    
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(RngKind::Lcg),
+            1 => Some(RngKind::Xorshift),
+            2 => Some(RngKind::Vip),
+            _ => None,
+        }
+    }
+}
+
+pub struct Rng {
+    kind: RngKind,
+    seed: u64,
+    state: u64,
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new(RngKind::default(), DEFAULT_SEED)
+    }
+}
+
+impl Rng {
+    pub fn new(kind: RngKind, seed: u64) -> Self {
+        let mut rng = Rng { kind, seed, state: 0 };
+        rng.reseed();
+        rng
+    }
+    
+    pub fn kind(&self) -> RngKind {
+        self.kind
+    }
+    
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    
+    /// Changer d'algorithme, l'état repart de la graine
+    pub fn set_kind(&mut self, kind: RngKind) {
+        self.kind = kind;
+        self.reseed();
+    }
+    
+    /// Changer de graine, la séquence recommence
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.reseed();
+    }
+    
+    /// Revenir au début de la séquence
+    pub fn reseed(&mut self) {
+        self.state = match self.kind {
+            RngKind::Lcg => self.seed & 0xFFFF_FFFF,
+            // xorshift ne doit jamais partir de 0
+            RngKind::Xorshift => if self.seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { self.seed },
+            RngKind::Vip => self.seed & 0xFFFF,
+        };
+    }
+    
+    /// Interruption 60 Hz (seul le générateur VIP en dépend)
+    pub fn tick(&mut self) {
+        if self.kind == RngKind::Vip {
+            let counter = (self.state as u8).wrapping_add(1);
+            self.state = (self.state & 0xFF00) | counter as u64;
+        }
+    }
+    
+    /// Tirer un octet
+    pub fn next_byte(&mut self, memory: &Memory) -> u8 {
+        match self.kind {
+            RngKind::Lcg => {
+                let state = (self.state as u32).wrapping_mul(1103515245).wrapping_add(12345);
+                self.state = state as u64;
+                (state >> 16) as u8
+            },
+            RngKind::Xorshift => {
+                let mut x = self.state;
+                x ^= x >> 12;
+                x ^= x << 25;
+                x ^= x >> 27;
+                self.state = x;
+                (x.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 56) as u8
+            },
+            RngKind::Vip => {
+                let counter = (self.state as u8).wrapping_add(1);
+                let value = ((self.state >> 8) as u8).wrapping_add(memory.peek_byte(counter as u16));
+                self.state = ((value as u64) << 8) | counter as u64;
+                value
+            },
+        }
+    }
+    
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        writer.u8(self.kind.id());
+        writer.u64(self.seed);
+        writer.u64(self.state);
+    }
+    
+    pub fn load_state(&mut self, reader: &mut StateReader) -> Result<(), String> {
+        let kind = reader.u8()?;
+        self.kind = RngKind::from_id(kind)
+            .ok_or_else(|| format!("Générateur aléatoire inconnu: {}", kind))?;
+        self.seed = reader.u64()?;
+        self.state = reader.u64()?;
+        Ok(())
+    }
+}
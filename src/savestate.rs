@@ -0,0 +1,184 @@
+//! Format binaire des sauvegardes d'état
+//! En-tête "F8ST", version, taille du contenu, contenu puis CRC32 du contenu
+//! Chaque composant écrit et relit ses champs dans le même ordre
+
+/// Signature en tête de chaque sauvegarde
+const MAGIC: &[u8; 4] = b"F8ST";
+
+/// À incrémenter dès que l'ordre ou la nature des champs change
+pub const STATE_VERSION: u16 = 5;
+
+/// Signature + version + taille
+const HEADER_SIZE: usize = 4 + 2 + 4;
+const CHECKSUM_SIZE: usize = 4;
+
+/// Emballer un contenu avec l'en-tête et la somme de contrôle
+pub fn encode(payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(HEADER_SIZE + payload.len() + CHECKSUM_SIZE);
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&STATE_VERSION.to_le_bytes());
+    data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    data.extend_from_slice(payload);
+    data.extend_from_slice(&crc32(payload).to_le_bytes());
+    data
+}
+
+/// Vérifier l'en-tête et la somme de contrôle, retourne le contenu
+pub fn decode(data: &[u8]) -> Result<&[u8], String> {
+    if data.len() < HEADER_SIZE + CHECKSUM_SIZE || &data[..4] != MAGIC {
+        return Err(String::from("Ce n'est pas une sauvegarde Ferris-8"));
+    }
+    
+    let version = u16::from_le_bytes([data[4], data[5]]);
+    if version != STATE_VERSION {
+        return Err(format!("Version de sauvegarde {} non supportée (attendue: {})", version, STATE_VERSION));
+    }
+    
+    let length = u32::from_le_bytes([data[6], data[7], data[8], data[9]]) as usize;
+    if data.len() != HEADER_SIZE + length + CHECKSUM_SIZE {
+        return Err(String::from("Sauvegarde tronquée"));
+    }
+    
+    let payload = &data[HEADER_SIZE..HEADER_SIZE + length];
+    let expected = &data[HEADER_SIZE + length..];
+    if crc32(payload).to_le_bytes() != expected {
+        return Err(String::from("Sauvegarde corrompue (CRC32 invalide)"));
+    }
+    
+    Ok(payload)
+}
+
+/// CRC32 IEEE (polynôme 0xEDB88320), comme zlib
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Écriture séquentielle des champs en little-endian
+#[derive(Default)]
+pub struct StateWriter {
+    data: Vec<u8>,
+}
+
+impl StateWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+    
+    pub fn bool(&mut self, value: bool) {
+        self.data.push(value as u8);
+    }
+    
+    pub fn u16(&mut self, value: u16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+    
+    pub fn u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+    
+    pub fn u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+    
+    pub fn f64(&mut self, value: f64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+    
+    /// Tableau de taille fixe, sans préfixe de longueur
+    pub fn bytes(&mut self, values: &[u8]) {
+        self.data.extend_from_slice(values);
+    }
+    
+    /// Tableau de taille variable, précédé de sa longueur
+    pub fn vec(&mut self, values: &[u8]) {
+        self.u32(values.len() as u32);
+        self.bytes(values);
+    }
+    
+    pub fn finish(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// Lecture séquentielle, chaque accès échoue proprement en fin de données
+pub struct StateReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> StateReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        StateReader { data, position: 0 }
+    }
+    
+    fn take(&mut self, count: usize) -> Result<&'a [u8], String> {
+        let end = self.position + count;
+        if end > self.data.len() {
+            return Err(String::from("Sauvegarde tronquée"));
+        }
+        let slice = &self.data[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+    
+    pub fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+    
+    pub fn bool(&mut self) -> Result<bool, String> {
+        Ok(self.u8()? != 0)
+    }
+    
+    pub fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+    
+    pub fn u32(&mut self) -> Result<u32, String> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+    
+    pub fn u64(&mut self) -> Result<u64, String> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+    
+    pub fn f64(&mut self) -> Result<f64, String> {
+        Ok(f64::from_bits(self.u64()?))
+    }
+    
+    /// Remplir un tableau de taille fixe
+    pub fn bytes(&mut self, out: &mut [u8]) -> Result<(), String> {
+        out.copy_from_slice(self.take(out.len())?);
+        Ok(())
+    }
+    
+    /// Relire un tableau écrit avec `StateWriter::vec`
+    pub fn vec(&mut self) -> Result<Vec<u8>, String> {
+        let length = self.u32()? as usize;
+        Ok(self.take(length)?.to_vec())
+    }
+    
+    /// Vérifier qu'il ne reste rien à lire
+    pub fn finish(self) -> Result<(), String> {
+        if self.position != self.data.len() {
+            return Err(String::from("Données inattendues en fin de sauvegarde"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+//! Symboles de debug : labels et lignes source d'un programme assemblé
+//! Permet d'afficher `main+0x12 (game.8o:42)` au lieu de `0x0214`
+//!
+//! Format texte (une directive par ligne, `#` commente) :
+//! ```text
+//! file game.8o
+//! label main 0x0202
+//! line 0x0202 42
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::assembler::Program;
+
+/// Nom de fichier utilisé quand la table n'en précise pas
+const DEFAULT_FILE: &str = "source";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolMap {
+    file: String,
+    /// Adresse -> label (le premier défini si plusieurs partagent l'adresse)
+    labels: BTreeMap<u16, String>,
+    /// Adresse d'instruction -> ligne source
+    lines: BTreeMap<u16, usize>,
+}
+
+impl Default for SymbolMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SymbolMap {
+    pub fn new() -> Self {
+        SymbolMap {
+            file: String::from(DEFAULT_FILE),
+            labels: BTreeMap::new(),
+            lines: BTreeMap::new(),
+        }
+    }
+    
+    /// Symboles produits par l'assembleur
+    pub fn from_program(program: &Program, file: &str) -> Self {
+        let mut symbols = SymbolMap::new();
+        symbols.file = file.to_string();
+        for (name, &address) in &program.labels {
+            symbols.add_label(name, address);
+        }
+        for line in &program.lines {
+            symbols.lines.entry(line.address).or_insert(line.line);
+        }
+        symbols
+    }
+    
+    /// Lire une table au format texte
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut symbols = SymbolMap::new();
+        
+        for (index, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            
+            let error = |message: &str| format!("Symboles ligne {}: {}", index + 1, message);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["file", name] => symbols.file = name.to_string(),
+                ["label", name, address] => {
+                    let address = parse_address(address).ok_or_else(|| error("adresse invalide"))?;
+                    symbols.add_label(name, address);
+                },
+                ["line", address, number] => {
+                    let address = parse_address(address).ok_or_else(|| error("adresse invalide"))?;
+                    let number = number.parse().map_err(|_| error("numéro de ligne invalide"))?;
+                    symbols.lines.insert(address, number);
+                },
+                _ => return Err(error(&format!("directive inconnue: {}", line))),
+            }
+        }
+        
+        Ok(symbols)
+    }
+    
+    /// Écrire la table au format lu par `parse`
+    pub fn to_text(&self) -> String {
+        let mut text = format!("file {}\n", self.file);
+        for (address, name) in &self.labels {
+            text.push_str(&format!("label {} 0x{:04X}\n", name, address));
+        }
+        for (address, line) in &self.lines {
+            text.push_str(&format!("line 0x{:04X} {}\n", address, line));
+        }
+        text
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.lines.is_empty()
+    }
+    
+    pub fn clear(&mut self) {
+        *self = SymbolMap::new();
+    }
+    
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+    
+    pub fn add_label(&mut self, name: &str, address: u16) {
+        self.labels.entry(address).or_insert_with(|| name.to_string());
+    }
+    
+    /// Adresse d'un label
+    pub fn address_of(&self, name: &str) -> Option<u16> {
+        self.labels.iter()
+            .find(|(_, label)| label.as_str() == name)
+            .map(|(&address, _)| address)
+    }
+    
+    /// Label exactement à cette adresse
+    pub fn label_at(&self, address: u16) -> Option<&str> {
+        self.labels.get(&address).map(String::as_str)
+    }
+    
+    /// Label le plus proche en dessous de l'adresse, avec le décalage
+    pub fn nearest_label(&self, address: u16) -> Option<(&str, u16)> {
+        self.labels.range(..=address)
+            .next_back()
+            .map(|(&start, name)| (name.as_str(), address - start))
+    }
+    
+    /// Ligne source de l'instruction à cette adresse
+    pub fn line_at(&self, address: u16) -> Option<usize> {
+        self.lines.get(&address).copied()
+    }
+    
+    /// `main+0x12 (game.8o:42)`, ou `0x0214` sans symbole
+    pub fn describe(&self, address: u16) -> String {
+        let mut text = match self.nearest_label(address) {
+            Some((name, 0)) => name.to_string(),
+            Some((name, offset)) => format!("{}+0x{:X}", name, offset),
+            None => format!("0x{:04X}", address),
+        };
+        if let Some(line) = self.line_at(address) {
+            text.push_str(&format!(" ({}:{})", self.file, line));
+        }
+        text
+    }
+}
+
+fn parse_address(text: &str) -> Option<u16> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u16::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
@@ -0,0 +1,342 @@
+//! Trace d'exécution : historique circulaire des dernières instructions
+//! Chaque entrée garde le cycle, le PC, l'opcode, I et les registres modifiés
+//! (état après l'instruction). Export en texte ou en binaire compact, et
+//! comparaison avec la trace d'un émulateur de référence, dans l'ordre des
+//! instructions exécutées
+//!
+//! Format texte, une instruction par ligne (`;` commente la fin de ligne) :
+//! ```text
+//! 42 0204: 6A02 I=0000 VA=02
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::savestate::{StateReader, StateWriter};
+
+const MAGIC: &[u8; 4] = b"F8TR";
+const TRACE_VERSION: u16 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceEntry {
+    /// Numéro de l'instruction depuis le chargement (1 = première)
+    pub cycle: u64,
+    pub pc: u16,
+    pub opcode: u16,
+    /// Absent des traces de référence qui ne le donnent pas
+    pub i: Option<u16>,
+    /// Registres modifiés, bit n = Vn
+    pub changed: u16,
+    /// Valeurs des registres ; seules celles de `changed` sont significatives
+    pub values: [u8; 16],
+}
+
+impl TraceEntry {
+    pub fn new(cycle: u64, pc: u16, opcode: u16, i: u16, before: &[u8; 16], after: &[u8; 16]) -> Self {
+        let changed = (0..16)
+            .filter(|&r| before[r] != after[r])
+            .fold(0, |mask, r| mask | (1 << r));
+        TraceEntry { cycle, pc, opcode, i: Some(i), changed, values: *after }
+    }
+    
+    pub fn changed_registers(&self) -> impl Iterator<Item = (usize, u8)> + '_ {
+        (0..16)
+            .filter(|&r| self.changed & (1 << r) != 0)
+            .map(|r| (r, self.values[r]))
+    }
+    
+    /// Ligne au format texte
+    pub fn to_text(&self) -> String {
+        let mut line = format!("{} {:04X}: {:04X}", self.cycle, self.pc, self.opcode);
+        if let Some(i) = self.i {
+            line.push_str(&format!(" I={:04X}", i));
+        }
+        for (register, value) in self.changed_registers() {
+            line.push_str(&format!(" V{:X}={:02X}", register, value));
+        }
+        line
+    }
+    
+    /// Relire une ligne texte, None si elle est vide ou commentée
+    pub fn parse_line(line: &str) -> Result<Option<Self>, String> {
+        let code = line.split(';').next().unwrap_or("");
+        let mut fields = code.split_whitespace();
+        let Some(cycle) = fields.next() else {
+            return Ok(None);
+        };
+        
+        let cycle = cycle.parse().map_err(|_| format!("cycle invalide: {}", cycle))?;
+        let pc = fields.next().ok_or("PC manquant")?;
+        let pc = parse_hex(pc.trim_end_matches(':')).ok_or_else(|| format!("PC invalide: {}", pc))?;
+        let opcode = fields.next().ok_or("opcode manquant")?;
+        let opcode = parse_hex(opcode).ok_or_else(|| format!("opcode invalide: {}", opcode))?;
+        
+        let mut entry = TraceEntry { cycle, pc, opcode, i: None, changed: 0, values: [0; 16] };
+        for field in fields {
+            let (name, value) = field.split_once('=')
+                .ok_or_else(|| format!("champ invalide: {}", field))?;
+            let value = parse_hex(value).ok_or_else(|| format!("valeur invalide: {}", field))?;
+            let name = name.to_ascii_uppercase();
+            if name == "I" {
+                entry.i = Some(value);
+                continue;
+            }
+            let register = name.strip_prefix('V')
+                .filter(|digit| digit.len() == 1)
+                .and_then(|digit| usize::from_str_radix(digit, 16).ok())
+                .ok_or_else(|| format!("registre invalide: {}", field))?;
+            entry.changed |= 1 << register;
+            entry.values[register] = value as u8;
+        }
+        Ok(Some(entry))
+    }
+    
+    /// Première différence avec l'entrée de référence
+    fn difference(&self, expected: &TraceEntry) -> Option<String> {
+        if self.pc != expected.pc {
+            return Some(format!("PC 0x{:04X} au lieu de 0x{:04X}", self.pc, expected.pc));
+        }
+        if self.opcode != expected.opcode {
+            return Some(format!("opcode {:04X} au lieu de {:04X}", self.opcode, expected.opcode));
+        }
+        if let (Some(i), Some(expected_i)) = (self.i, expected.i)
+            && i != expected_i
+        {
+            return Some(format!("I=0x{:04X} au lieu de 0x{:04X}", i, expected_i));
+        }
+        for register in 0..16 {
+            let mask = 1 << register;
+            let ours = (self.changed & mask != 0).then_some(self.values[register]);
+            let theirs = (expected.changed & mask != 0).then_some(expected.values[register]);
+            let describe = |value: Option<u8>| value.map_or(String::from("inchangé"), |v| format!("{:02X}", v));
+            if ours != theirs {
+                return Some(format!("V{:X} {} au lieu de {}", register, describe(ours), describe(theirs)));
+            }
+        }
+        None
+    }
+}
+
+/// Première instruction où deux traces divergent
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Cycle de l'instruction dans notre trace (celui qui suit sa fin si elle
+    /// est terminée)
+    pub cycle: u64,
+    /// Absente si notre trace est terminée
+    pub actual: Option<TraceEntry>,
+    /// Absente si la référence est terminée
+    pub expected: Option<TraceEntry>,
+    pub reason: String,
+}
+
+impl Divergence {
+    pub fn describe(&self) -> String {
+        let line = |entry: &Option<TraceEntry>| entry.map_or(String::from("(trace terminée)"), |e| e.to_text());
+        format!(
+            "Divergence au cycle {}: {}\n  obtenu:  {}\n  attendu: {}",
+            self.cycle, self.reason, line(&self.actual), line(&self.expected)
+        )
+    }
+}
+
+pub struct Tracer {
+    /// Nombre maximal d'entrées, 0 = désactivé
+    capacity: usize,
+    entries: VecDeque<TraceEntry>,
+}
+
+impl Default for Tracer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tracer {
+    pub fn new() -> Self {
+        Tracer {
+            capacity: 0,
+            entries: VecDeque::new(),
+        }
+    }
+    
+    /// Changer la taille de l'historique, vide ce qui était enregistré
+    pub fn configure(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.entries = VecDeque::with_capacity(capacity.min(1 << 16));
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+    
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+    
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &TraceEntry> + ExactSizeIterator {
+        self.entries.iter()
+    }
+    
+    /// Oublier les entrées postérieures à `cycle` (après un retour en arrière)
+    pub fn discard_after(&mut self, cycle: u64) {
+        while self.entries.back().is_some_and(|entry| entry.cycle > cycle) {
+            self.entries.pop_back();
+        }
+    }
+    
+    /// Ajouter une entrée, la plus ancienne est oubliée si le tampon est plein
+    pub fn record(&mut self, entry: TraceEntry) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+    
+    /// Export binaire : magic, version, nombre d'entrées, puis pour chacune
+    /// cycle, PC, opcode, I, masque des registres modifiés et leurs valeurs
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        writer.bytes(MAGIC);
+        writer.u16(TRACE_VERSION);
+        writer.u32(self.entries.len() as u32);
+        for entry in &self.entries {
+            writer.u64(entry.cycle);
+            writer.u16(entry.pc);
+            writer.u16(entry.opcode);
+            writer.u16(entry.i.unwrap_or(0));
+            writer.u16(entry.changed);
+            for (_, value) in entry.changed_registers() {
+                writer.u8(value);
+            }
+        }
+        writer.finish()
+    }
+    
+    /// Relire une trace, binaire (`to_binary`) ou texte
+    pub fn parse(data: &[u8]) -> Result<Vec<TraceEntry>, String> {
+        if data.starts_with(MAGIC) {
+            return parse_binary(data);
+        }
+        
+        let text = std::str::from_utf8(data).map_err(|_| String::from("Trace ni binaire ni texte"))?;
+        let mut entries = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if let Some(entry) = TraceEntry::parse_line(line)
+                .map_err(|e| format!("Trace ligne {}: {}", index + 1, e))?
+            {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+    
+    /// Comparer avec une trace de référence, instruction exécutée par instruction
+    /// exécutée : les émulateurs ne comptent pas tous les cycles d'attente de
+    /// Fx0A ou de Dxyn de la même façon, donc les numéros de cycle sont ignorés.
+    /// Le début est calé sur la première instruction de la référence qui a le PC
+    /// et l'opcode de notre plus ancienne (le tampon circulaire a pu oublier le
+    /// début), ou l'inverse si c'est la référence qui commence plus tard.
+    /// Une trace qui se termine avant l'autre est une divergence
+    pub fn diff(&self, reference: &[TraceEntry]) -> Result<Option<Divergence>, String> {
+        let actual = executed(self.entries.iter());
+        let expected = executed(reference.iter());
+        let same = |a: &TraceEntry, b: &TraceEntry| a.pc == b.pc && a.opcode == b.opcode;
+        let (actual, expected) = match (actual.first(), expected.first()) {
+            (Some(oldest), Some(first)) => {
+                if let Some(start) = expected.iter().position(|e| same(e, oldest)) {
+                    (&actual[..], &expected[start..])
+                } else if let Some(start) = actual.iter().position(|e| same(e, first)) {
+                    (&actual[start..], &expected[..])
+                } else {
+                    return Err(format!(
+                        "La trace ne couvre plus le début de la référence (plus ancienne instruction: {})",
+                        oldest.to_text()
+                    ));
+                }
+            },
+            _ => (&actual[..], &expected[..]),
+        };
+        
+        for (ours, theirs) in actual.iter().zip(expected) {
+            if let Some(reason) = ours.difference(theirs) {
+                return Ok(Some(Divergence { cycle: ours.cycle, actual: Some(*ours), expected: Some(*theirs), reason }));
+            }
+        }
+        let common = actual.len().min(expected.len());
+        let next_cycle = actual.last().map_or(0, |last| last.cycle + 1);
+        Ok(match (actual.get(common), expected.get(common)) {
+            (None, Some(theirs)) => Some(Divergence {
+                cycle: next_cycle,
+                actual: None,
+                expected: Some(*theirs),
+                reason: String::from("trace terminée, la référence continue"),
+            }),
+            (Some(ours), None) => Some(Divergence {
+                cycle: ours.cycle,
+                actual: Some(*ours),
+                expected: None,
+                reason: String::from("référence terminée, la trace continue"),
+            }),
+            _ => None,
+        })
+    }
+}
+
+/// Instructions exécutées dans l'ordre : une instruction répétée sur place
+/// (Fx0A ou Dxyn qui attendent, saut sur elle-même) ne compte qu'une fois,
+/// avec l'effet de sa dernière exécution
+fn executed<'a>(entries: impl Iterator<Item = &'a TraceEntry>) -> Vec<TraceEntry> {
+    let mut executed: Vec<TraceEntry> = Vec::new();
+    for entry in entries {
+        match executed.last_mut() {
+            Some(last) if last.pc == entry.pc && last.opcode == entry.opcode => *last = *entry,
+            _ => executed.push(*entry),
+        }
+    }
+    executed
+}
+
+fn parse_binary(data: &[u8]) -> Result<Vec<TraceEntry>, String> {
+    let mut reader = StateReader::new(data);
+    let mut magic = [0; 4];
+    reader.bytes(&mut magic)?;
+    
+    let version = reader.u16()?;
+    if version != TRACE_VERSION {
+        return Err(format!("Version de trace non supportée: {}", version));
+    }
+    
+    let count = reader.u32()? as usize;
+    let mut entries = Vec::with_capacity(count.min(data.len()));
+    for _ in 0..count {
+        let mut entry = TraceEntry {
+            cycle: reader.u64()?,
+            pc: reader.u16()?,
+            opcode: reader.u16()?,
+            i: Some(reader.u16()?),
+            changed: reader.u16()?,
+            values: [0; 16],
+        };
+        for register in 0..16 {
+            if entry.changed & (1 << register) != 0 {
+                entry.values[register] = reader.u8()?;
+            }
+        }
+        entries.push(entry);
+    }
+    reader.finish()?;
+    Ok(entries)
+}
+
+fn parse_hex(text: &str) -> Option<u16> {
+    let digits = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")).unwrap_or(text);
+    u16::from_str_radix(digits, 16).ok()
+}
@@ -58,6 +58,24 @@ else
     exit 1
 fi
 
+# Test 2b: Compilation native du cœur, sans web-sys
+test_info "Test 2b: Compilation native (sans feature wasm)"
+if cargo check --quiet --no-default-features; then
+    test_pass "Cœur compilable en natif"
+else
+    test_fail "Le cœur dépend encore des bindings web"
+    exit 1
+fi
+
+# Test 2c: Tests unitaires et ROMs de conformité
+test_info "Test 2c: Tests natifs (opcodes et ROMs de test)"
+if cargo test --quiet --no-default-features; then
+    test_pass "Tous les tests natifs passent"
+else
+    test_fail "Des tests natifs échouent"
+    exit 1
+fi
+
 # Test 3: Target WebAssembly
 test_info "Test 3: Target WebAssembly"
 if rustup target list --installed | grep -q "wasm32-unknown-unknown"; then
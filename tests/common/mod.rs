@@ -0,0 +1,96 @@
+//! Outils partagés par les tests natifs : machine prête à l'emploi,
+//! exécution pas à pas et empreinte de l'écran
+
+#![allow(dead_code)]
+
+use ferris8::{assembler, Cpu, Platform, Quirks};
+
+/// Adresse de chargement des programmes
+pub const START: u16 = 0x200;
+
+/// CPU sur la plateforme donnée avec le programme chargé en 0x200
+pub fn machine(platform: Platform, program: &[u16]) -> Cpu {
+    let rom: Vec<u8> = program.iter().flat_map(|word| word.to_be_bytes()).collect();
+    machine_with_rom(platform, &rom)
+}
+
+/// CPU sur la plateforme donnée (quirks par défaut) avec une ROM déjà assemblée
+pub fn machine_with_rom(platform: Platform, rom: &[u8]) -> Cpu {
+    let mut cpu = Cpu::new();
+    cpu.set_platform(platform);
+    assert!(cpu.load_rom(rom), "programme de test refusé");
+    cpu
+}
+
+/// Assembler une ROM de test (tests/roms/*.8o)
+pub fn assemble(name: &str) -> Vec<u8> {
+    let path = format!("{}/tests/roms/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let source = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    match assembler::assemble(&source) {
+        Ok(program) => program.rom,
+        Err(e) => panic!("{}: {}", name, e),
+    }
+}
+
+/// Chip-8 avec les quirks du COSMAC VIP, sauf l'attente du rafraîchissement
+pub fn chip8(program: &[u16]) -> Cpu {
+    let mut cpu = machine(Platform::Chip8, program);
+    cpu.quirks = Quirks { display_wait: false, ..Quirks::vip() };
+    cpu
+}
+
+pub fn schip(program: &[u16]) -> Cpu {
+    machine(Platform::SuperChip, program)
+}
+
+pub fn xo_chip(program: &[u16]) -> Cpu {
+    machine(Platform::XoChip, program)
+}
+
+/// Exécuter `count` instructions
+pub fn step(cpu: &mut Cpu, count: usize) {
+    for _ in 0..count {
+        cpu.cycle();
+    }
+}
+
+/// Empreinte FNV-1a 64 bits de l'écran (index de couleur par pixel, dimensions incluses)
+pub fn screen_hash(cpu: &Cpu) -> u64 {
+    let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+    let size = [cpu.display.width() as u8, cpu.display.height() as u8];
+    for byte in size.into_iter().chain(cpu.display.get_plane_buffer()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01B3);
+    }
+    hash
+}
+
+/// Appui ou relâchement d'une touche au début d'une frame
+#[derive(Clone, Copy)]
+pub struct KeyEvent {
+    pub frame: u32,
+    pub key: u8,
+    pub pressed: bool,
+}
+
+pub const fn press(frame: u32, key: u8) -> KeyEvent {
+    KeyEvent { frame, key, pressed: true }
+}
+
+pub const fn release(frame: u32, key: u8) -> KeyEvent {
+    KeyEvent { frame, key, pressed: false }
+}
+
+/// Faire tourner une machine `frames` frames de `cycles` instructions avec des touches scriptées
+pub fn run_frames(cpu: &mut Cpu, frames: u32, cycles: u32, keys: &[KeyEvent]) {
+    for frame in 0..frames {
+        for event in keys.iter().filter(|event| event.frame == frame) {
+            if event.pressed {
+                cpu.key_down(event.key);
+            } else {
+                cpu.key_up(event.key);
+            }
+        }
+        cpu.run_frame(cycles);
+    }
+}
@@ -0,0 +1,138 @@
+//! ROMs de test (tests/roms/*.8o) exécutées sans interface : l'écran final
+//! est comparé à l'écran de réussite attendu pour chaque plateforme
+
+mod common;
+
+use common::{assemble, machine_with_rom, press, release, run_frames, KeyEvent};
+use ferris8::{Cpu, Platform};
+
+/// Instructions par frame, comme l'interface web par défaut
+const CYCLES_PER_FRAME: u32 = 100;
+
+fn run_rom(name: &str, platform: Platform, frames: u32, keys: &[KeyEvent]) -> Cpu {
+    let mut cpu = machine_with_rom(platform, &assemble(name));
+    run_frames(&mut cpu, frames, CYCLES_PER_FRAME, keys);
+    assert!(!cpu.is_halted(), "{} arrêté: {:?}", name, cpu.halt_reason);
+    assert_eq!(cpu.error_count, 0, "{}: erreurs d'exécution", name);
+    cpu
+}
+
+/// Coche de réussite et croix d'échec des ROMs de test
+const OK: [u8; 4] = [0x08, 0x10, 0xA0, 0x40];
+
+/// Chiffres 5, A et B de la police Chip-8 standard
+const FONT_5: [u8; 5] = [0xF0, 0x80, 0xF0, 0x10, 0xF0];
+const FONT_A: [u8; 5] = [0xF0, 0x90, 0xF0, 0x90, 0x90];
+const FONT_B: [u8; 5] = [0xE0, 0x90, 0xE0, 0x90, 0xE0];
+
+/// Carrés plein (quirk actif) et vide (quirk inactif) du test des quirks
+const QUIRK_ON: [u8; 4] = [0xF0, 0xF0, 0xF0, 0xF0];
+const QUIRK_OFF: [u8; 4] = [0xF0, 0x90, 0x90, 0xF0];
+
+/// Écran attendu en basse résolution, dessiné à la main d'après la
+/// disposition documentée en tête de chaque ROM : il ne dépend ni de
+/// l'assembleur ni du CPU testés
+struct Screen {
+    pixels: [[bool; 64]; 32],
+}
+
+impl Screen {
+    fn new() -> Self {
+        Screen { pixels: [[false; 64]; 32] }
+    }
+    
+    fn sprite(mut self, x: usize, y: usize, rows: &[u8]) -> Self {
+        for (dy, row) in rows.iter().enumerate() {
+            for dx in 0..8 {
+                if row & (0x80 >> dx) != 0 {
+                    self.pixels[y + dy][x + dx] ^= true;
+                }
+            }
+        }
+        self
+    }
+    
+    /// Résultats de 6 pixels de large à partir de (1, y), `per_line` par ligne
+    fn marks(self, y: usize, sprites: &[[u8; 4]], per_line: usize) -> Self {
+        sprites.iter().enumerate().fold(self, |screen, (index, rows)| {
+            screen.sprite(1 + 6 * (index % per_line), y + 6 * (index / per_line), rows)
+        })
+    }
+    
+    fn to_text(&self) -> String {
+        self.pixels.iter()
+            .map(|row| row.iter().map(|&on| if on { '#' } else { '.' }).collect::<String>() + "\n")
+            .collect()
+    }
+}
+
+fn assert_screen(cpu: &Cpu, expected: &Screen) {
+    let actual = cpu.display.to_text('#', '.');
+    assert_eq!((cpu.display.width(), cpu.display.height()), (64, 32));
+    assert!(actual == expected.to_text(), "écran inattendu :\n{}\nattendu :\n{}", actual, expected.to_text());
+}
+
+#[test]
+fn opcode_test_rom() {
+    let expected = Screen::new().marks(1, &[OK; 22], 10);
+    for platform in [Platform::Chip8, Platform::SuperChip, Platform::XoChip] {
+        let cpu = run_rom("opcodes.8o", platform, 60, &[]);
+        assert_screen(&cpu, &expected);
+    }
+}
+
+#[test]
+fn flags_test_rom() {
+    let expected = Screen::new().marks(1, &[OK; 8], 8).marks(7, &[OK; 6], 6);
+    for platform in [Platform::Chip8, Platform::SuperChip, Platform::XoChip] {
+        let cpu = run_rom("flags.8o", platform, 30, &[]);
+        assert_screen(&cpu, &expected);
+    }
+}
+
+/// Quirks attendus dans l'ordre de la ROM : vf_reset, memory_increment,
+/// shift, jump_vx, clip, display_wait (tableau du test quirks de Timendus)
+fn quirk_screen(quirks: [bool; 6]) -> Screen {
+    Screen::new().marks(1, &quirks.map(|on| if on { QUIRK_ON } else { QUIRK_OFF }), 6)
+}
+
+#[test]
+fn quirks_test_rom_chip8() {
+    let cpu = run_rom("quirks.8o", Platform::Chip8, 30, &[]);
+    assert_screen(&cpu, &quirk_screen([true, true, false, false, true, true]));
+}
+
+#[test]
+fn quirks_test_rom_schip() {
+    let cpu = run_rom("quirks.8o", Platform::SuperChip, 30, &[]);
+    assert_screen(&cpu, &quirk_screen([false, false, true, true, true, false]));
+}
+
+#[test]
+fn quirks_test_rom_xo_chip() {
+    let cpu = run_rom("quirks.8o", Platform::XoChip, 30, &[]);
+    assert_screen(&cpu, &quirk_screen([false, true, false, false, false, false]));
+}
+
+const KEYPAD_SCRIPT: [KeyEvent; 8] = [
+    press(10, 0x5), release(15, 0x5),
+    press(20, 0xA), release(25, 0xA),
+    press(30, 0x7), release(45, 0x7),
+    press(55, 0xB), release(58, 0xB),
+];
+
+#[test]
+fn keypad_test_rom() {
+    // 7 encore tenue : seule la première coche est dessinée
+    let expected = Screen::new().sprite(1, 1, &FONT_5).sprite(7, 1, &FONT_A).sprite(14, 2, &OK);
+    let cpu = run_rom("keypad.8o", Platform::Chip8, 40, &KEYPAD_SCRIPT);
+    assert_screen(&cpu, &expected);
+    
+    // B enfoncée mais pas encore relâchée : Fx0A attend toujours
+    let expected = expected.sprite(20, 2, &OK);
+    let cpu = run_rom("keypad.8o", Platform::Chip8, 57, &KEYPAD_SCRIPT);
+    assert_screen(&cpu, &expected);
+    
+    let cpu = run_rom("keypad.8o", Platform::Chip8, 70, &KEYPAD_SCRIPT);
+    assert_screen(&cpu, &expected.sprite(27, 1, &FONT_B));
+}
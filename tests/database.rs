@@ -0,0 +1,208 @@
+//! Base de ROMs : SHA-1, lecture de `programs.json` et réglages appliqués
+//! au chargement
+
+use ferris8::{sha1_hex, Emulator, GamepadInput, KeyMap, Platform, Quirks, RomDatabase};
+
+const PONG: &[u8] = include_bytes!("../web/roms/Pong.ch8");
+
+const PROGRAMS: &str = r##"[
+    {
+        "title": "Jeu de test",
+        "authors": ["Ferris"],
+        "roms": {
+            "0123456789ABCDEF0123456789abcdef01234567": {
+                "platforms": ["megachip8", "superchip", "xochip"],
+                "quirkyPlatforms": { "superchip": { "shift": false, "wrap": true } },
+                "tickrate": 200,
+                "keys": { "left": 7, "right": 9, "a": 6 },
+                "colors": { "pixels": ["#101820", "#FEE715"] }
+            },
+            "89abcdef0123456789abcdef0123456789abcdef": {
+                "platforms": ["chip8x"]
+            }
+        }
+    }
+]"##;
+
+#[test]
+fn sha1_test_vectors() {
+    assert_eq!(sha1_hex(b""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+    assert_eq!(sha1_hex(b"abc"), "a9993e364706816aba3e25717850c26c9cd0d89d");
+    // Deux blocs : le bourrage ne tient plus dans le premier
+    assert_eq!(sha1_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+}
+
+#[test]
+fn reads_programs_json() {
+    let database = RomDatabase::from_json(PROGRAMS).unwrap();
+    // La ROM chip8x n'est pas émulée
+    assert_eq!(database.len(), 1);
+    assert!(database.find("89abcdef0123456789abcdef0123456789abcdef").is_none());
+    
+    let info = database.find("0123456789abcdef0123456789abcdef01234567").unwrap();
+    assert_eq!(info.title, "Jeu de test");
+    assert_eq!(info.authors, ["Ferris"]);
+    assert_eq!(info.platform, Platform::SuperChip);
+    assert_eq!(info.quirks, Quirks { shift: false, clip: false, ..Quirks::schip() });
+    assert_eq!(info.cycles_per_frame, 200);
+    assert_eq!(info.palette, [0x101820, 0xFEE715]);
+    
+    let keys = info.key_map(&KeyMap::new());
+    assert_eq!(keys.key_for("ArrowLeft"), Some(0x7));
+    assert_eq!(keys.key_for("Space"), Some(0x6));
+    assert_eq!(keys.key_for("ArrowUp"), None);
+    let pad = info.gamepad_map(0.3).unwrap();
+    assert_eq!(pad.inputs_for(0x9), [GamepadInput::from_name("right").unwrap(), GamepadInput::from_name("lx+").unwrap()]);
+    assert_eq!(pad.dead_zone(), 0.3);
+    
+    assert!(RomDatabase::from_json(r#"{"roms": {}}"#).is_err());
+    assert!(RomDatabase::from_json(r#"[{"title": "X", "roms": {"abc": {"platforms": []}}}]"#).is_err());
+    assert!(RomDatabase::from_json(&PROGRAMS.replace("200", "0")).is_err());
+}
+
+#[test]
+fn bundled_database_knows_pong() {
+    let database = RomDatabase::bundled();
+    let info = database.lookup(PONG).unwrap();
+    assert_eq!(info.title, "Pong");
+    assert_eq!(info.platform, Platform::Chip8);
+    assert_eq!(info.cycles_per_frame, 15);
+}
+
+/// Chaque ROM de `roms to test/` connue de la base tourne dix secondes sans
+/// erreur avec les réglages de son entrée
+#[test]
+fn bundled_entries_run_cleanly() {
+    let directory = format!("{}/roms to test", env!("CARGO_MANIFEST_DIR"));
+    let mut checked = 0;
+    for file in std::fs::read_dir(&directory).unwrap() {
+        let path = file.unwrap().path();
+        let rom = std::fs::read(&path).unwrap();
+        let mut emulator = Emulator::new();
+        // Les ROMs inconnues trop grandes pour Chip-8 sont refusées
+        if !emulator.load_rom(&rom) || emulator.get_rom_title().is_none() {
+            continue;
+        }
+        emulator.start();
+        for _ in 0..600 {
+            emulator.run_frame(emulator.get_rom_cycles_per_frame());
+        }
+        assert!(!emulator.is_halted(), "{}: {:?}", path.display(), emulator.get_halt_message());
+        assert_eq!(emulator.get_error_count(), 0, "{}: {}", path.display(), emulator.describe_errors());
+        checked += 1;
+    }
+    assert_eq!(checked + 1, RomDatabase::bundled().len());
+}
+
+#[test]
+fn loading_a_known_rom_applies_its_settings() {
+    let mut emulator = Emulator::new();
+    assert!(emulator.set_platform("xo-chip"));
+    assert!(emulator.load_rom(PONG));
+    assert_eq!(emulator.get_platform(), "chip8");
+    assert_eq!(emulator.cpu().quirks, Quirks::vip());
+    assert_eq!(emulator.get_rom_title().as_deref(), Some("Pong"));
+    assert_eq!(emulator.get_rom_sha1(), sha1_hex(PONG));
+    assert_eq!(emulator.map_key("ArrowUp"), Some(0x1));
+    assert_eq!(emulator.map_key("ArrowDown"), Some(0x4));
+    
+    // Base désactivée : les réglages courants sont gardés
+    let mut emulator = Emulator::new();
+    emulator.set_rom_database_enabled(false);
+    assert!(emulator.set_platform("xo-chip"));
+    assert!(emulator.load_rom(PONG));
+    assert_eq!(emulator.get_platform(), "xo-chip");
+    assert_eq!(emulator.get_rom_cycles_per_frame(), 0);
+    assert_eq!(emulator.map_key("ArrowUp"), None);
+}
+
+#[test]
+fn imported_entries_replace_bundled_ones() {
+    let mut emulator = Emulator::new();
+    let size = emulator.rom_database_size();
+    let programs = PROGRAMS.replace("0123456789ABCDEF0123456789abcdef01234567", &sha1_hex(PONG));
+    assert_eq!(emulator.load_rom_database(&programs), Ok(1));
+    assert_eq!(emulator.rom_database_size(), size);
+    
+    assert!(emulator.load_rom(PONG));
+    assert_eq!(emulator.get_platform(), "schip");
+    assert_eq!(emulator.get_rom_cycles_per_frame(), 200);
+    assert_eq!(emulator.get_rom_palette(), [0x101820, 0xFEE715]);
+}
+
+#[test]
+fn user_settings_come_back_after_a_known_rom() {
+    let mut emulator = Emulator::new();
+    assert!(emulator.set_platform("xo-chip"));
+    assert!(emulator.set_quirk("shift", true));
+    let quirks = emulator.cpu().quirks;
+    let key_map = emulator.export_key_map();
+    
+    assert!(emulator.load_rom(PONG));
+    assert_eq!(emulator.get_platform(), "chip8");
+    
+    // ROM inconnue : plateforme, quirks et touches d'avant Pong
+    assert!(emulator.load_rom(&[0x12, 0x00]));
+    assert_eq!(emulator.get_rom_title(), None);
+    assert_eq!(emulator.get_platform(), "xo-chip");
+    assert_eq!(emulator.cpu().quirks, quirks);
+    assert_eq!(emulator.get_stack_depth(), 64);
+    assert_eq!(emulator.export_key_map(), key_map);
+}
+
+#[test]
+fn rejected_rom_keeps_user_settings() {
+    // ROM connue trop grande pour la plateforme que la base lui donne
+    let rom = vec![0x12; 0x1000];
+    let programs = PROGRAMS.replace("0123456789ABCDEF0123456789abcdef01234567", &sha1_hex(&rom))
+        .replace(r#"["megachip8", "superchip", "xochip"]"#, r#"["originalChip8"]"#);
+    let mut emulator = Emulator::new();
+    emulator.load_rom_database(&programs).unwrap();
+    assert!(emulator.set_platform("xo-chip"));
+    let key_map = emulator.export_key_map();
+    let previous = [0x60, 0x2A, 0x12, 0x02];
+    assert!(emulator.load_rom(&previous));
+    
+    assert!(!emulator.load_rom(&rom));
+    assert_eq!(emulator.get_rom_title(), None);
+    assert_eq!(emulator.get_platform(), "xo-chip");
+    assert_eq!(emulator.cpu().quirks, Quirks::xo_chip());
+    assert_eq!(emulator.export_key_map(), key_map);
+    
+    // La ROM précédente est toujours en mémoire, qui n'a pas été réduite
+    assert_eq!(emulator.cpu().memory.size(), 0x10000);
+    assert_eq!((0..4).map(|i| emulator.cpu().memory.peek_byte(0x200 + i)).collect::<Vec<_>>(), previous);
+    emulator.start();
+    emulator.run_frame(10);
+    assert!(!emulator.is_halted());
+    assert_eq!(emulator.cpu().v[0], 0x2A);
+}
+
+#[test]
+fn user_edits_during_a_known_rom_are_kept() {
+    let mut emulator = Emulator::new();
+    assert!(emulator.set_platform("xo-chip"));
+    let gamepad_map = emulator.export_gamepad_map();
+    
+    assert!(emulator.load_rom(PONG));
+    assert!(emulator.bind_key("KeyP", 0xC));
+    assert!(emulator.set_quirk("shift", true));
+    let key_map = emulator.export_key_map();
+    let quirks = emulator.cpu().quirks;
+    
+    // Touches et quirks modifiés restent ; la manette, intacte, revient
+    assert!(emulator.load_rom(&[0x12, 0x00]));
+    assert_eq!(emulator.get_platform(), "xo-chip");
+    assert_eq!(emulator.cpu().quirks, quirks);
+    assert_eq!(emulator.export_key_map(), key_map);
+    assert_eq!(emulator.export_gamepad_map(), gamepad_map);
+    assert_eq!(emulator.get_stack_depth(), 64);
+    
+    // Plateforme choisie pendant Pong : gardée avec ses quirks
+    assert!(emulator.load_rom(PONG));
+    assert!(emulator.set_platform("schip"));
+    assert!(emulator.load_rom(&[0x12, 0x00]));
+    assert_eq!(emulator.get_platform(), "schip");
+    assert_eq!(emulator.cpu().quirks, Quirks::schip());
+}
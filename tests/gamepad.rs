@@ -0,0 +1,63 @@
+//! Manettes : boutons, demi-axes avec zone morte et relevés successifs
+
+use ferris8::{Emulator, GamepadInput, GamepadMap, GamepadState};
+
+fn pad(buttons: &[usize], axes: &[f32]) -> GamepadState {
+    let mut state = GamepadState { buttons: vec![false; 17], axes: axes.to_vec() };
+    for &button in buttons {
+        state.buttons[button] = true;
+    }
+    state
+}
+
+#[test]
+fn input_names() {
+    assert_eq!(GamepadInput::from_name("Up"), Some(GamepadInput::Button(12)));
+    assert_eq!(GamepadInput::from_name("button20"), Some(GamepadInput::Button(20)));
+    assert_eq!(GamepadInput::from_name("ly-"), Some(GamepadInput::Axis { axis: 1, positive: false }));
+    assert_eq!(GamepadInput::from_name("axis5+"), Some(GamepadInput::Axis { axis: 5, positive: true }));
+    assert_eq!(GamepadInput::from_name("trigger"), None);
+    assert_eq!(GamepadInput::Axis { axis: 2, positive: true }.name(), "rx+");
+}
+
+#[test]
+fn buttons_and_sticks_with_dead_zone() {
+    let map = GamepadMap::new();
+    assert_eq!(map.pressed_keys(&pad(&[12, 0], &[])), (1 << 0x5) | (1 << 0x6));
+    // Stick gauche légèrement poussé à gauche : dans la zone morte
+    assert_eq!(map.pressed_keys(&pad(&[], &[-0.2, 0.0])), 0);
+    assert_eq!(map.pressed_keys(&pad(&[], &[-0.8, 0.9])), (1 << 0x7) | (1 << 0x8));
+    
+    let mut map = GamepadMap::from_preset("pong").unwrap();
+    map.set_dead_zone(0.1);
+    assert_eq!(map.pressed_keys(&pad(&[], &[0.0, -0.2, 0.0, 0.5])), (1 << 0x1) | (1 << 0xD));
+}
+
+#[test]
+fn json_round_trip() {
+    let mut map = GamepadMap::from_preset("vip").unwrap();
+    map.set_dead_zone(0.25);
+    map.bind(GamepadInput::Button(9), 0xF);
+    let json = map.to_json();
+    assert_eq!(GamepadMap::from_json(&json).unwrap().to_json(), json);
+    
+    assert!(GamepadMap::from_json(r#"{"keys": {"1": ["jump"]}}"#).is_err());
+    assert!(GamepadMap::from_json(r#"{"dead_zone": "0.3", "keys": {}}"#).is_err());
+    assert!(GamepadMap::from_json(r#"{"1": ["a"]}"#).is_err());
+}
+
+#[test]
+fn polling_only_changes_gamepad_keys() {
+    let mut emulator = Emulator::new();
+    emulator.key_down(0x6);
+    emulator.apply_gamepads(&[pad(&[12], &[])]);
+    assert_eq!(emulator.get_pressed_keys(), (1 << 0x5) | (1 << 0x6));
+    
+    // Deux manettes : les touches s'additionnent
+    emulator.apply_gamepads(&[pad(&[12], &[]), pad(&[13], &[])]);
+    assert_eq!(emulator.get_gamepad_keys(), (1 << 0x5) | (1 << 0x8));
+    
+    // Relâcher la manette ne relâche pas la touche tenue au clavier
+    emulator.apply_gamepads(&[]);
+    assert_eq!(emulator.get_pressed_keys(), 1 << 0x6);
+}
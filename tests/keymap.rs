@@ -0,0 +1,56 @@
+//! Mapping clavier : presets, associations multiples et format JSON
+
+use ferris8::KeyMap;
+
+#[test]
+fn default_map_is_the_letter_block() {
+    let map = KeyMap::new();
+    assert_eq!(map.key_for("Digit4"), Some(0xC));
+    assert_eq!(map.key_for("KeyX"), Some(0x0));
+    assert_eq!(map.key_for("KeyV"), Some(0xF));
+    assert_eq!(map.key_for("Numpad5"), None);
+    assert_eq!(KeyMap::from_preset("AZERTY"), Some(map));
+}
+
+#[test]
+fn numpad_preset_keeps_digits() {
+    let map = KeyMap::from_preset("numpad").unwrap();
+    for digit in 0..10u8 {
+        assert_eq!(map.key_for(&format!("Numpad{}", digit)), Some(digit));
+    }
+    assert_eq!(map.key_for("NumpadDecimal"), Some(0xF));
+    assert!(KeyMap::from_preset("dvorak").is_none());
+}
+
+#[test]
+fn several_codes_per_key_and_rebinding() {
+    let mut map = KeyMap::new();
+    assert!(map.bind("ArrowUp", 0x5));
+    assert_eq!(map.codes_for(0x5), ["KeyW", "ArrowUp"]);
+    assert!(map.bind("ArrowUp", 0x8));
+    assert_eq!(map.codes_for(0x5), ["KeyW"]);
+    assert!(!map.bind("ArrowUp", 0x10));
+    assert!(map.unbind("KeyW"));
+    assert_eq!(map.key_for("KeyW"), None);
+    map.clear_key(0x8);
+    assert!(map.codes_for(0x8).is_empty());
+}
+
+#[test]
+fn json_round_trip() {
+    let mut map = KeyMap::from_preset("numpad").unwrap();
+    map.bind("Space", 0x5);
+    let json = map.to_json();
+    assert_eq!(KeyMap::from_json(&json).unwrap().to_json(), json);
+    
+    let map = KeyMap::from_json(r#"{ "a": "Space", "2": ["KeyW", "ArrowUp"] }"#).unwrap();
+    assert_eq!(map.key_for("Space"), Some(0xA));
+    assert_eq!(map.key_for("ArrowUp"), Some(0x2));
+}
+
+#[test]
+fn invalid_json_is_rejected() {
+    for text in ["[]", r#"{"G": []}"#, r#"{"10": []}"#, r#"{"1": [3]}"#, r#"{"1": ["KeyA""#, "{} {}"] {
+        assert!(KeyMap::from_json(text).is_err(), "{}", text);
+    }
+}
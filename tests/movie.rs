@@ -0,0 +1,154 @@
+//! Films : un enregistrement rejoué sur un autre émulateur, quels que soient
+//! ses réglages, redonne exactement le même écran
+
+mod common;
+
+use common::{assemble, screen_hash};
+use ferris8::{assembler, Emulator, Movie, MovieInput};
+
+const CYCLES_PER_FRAME: u32 = 100;
+
+/// Appuis et relâchements du test clavier, (frame, touche, appui)
+const KEYPAD_SCRIPT: [(u32, u8, bool); 8] = [
+    (10, 0x5, true), (15, 0x5, false),
+    (20, 0xA, true), (25, 0xA, false),
+    (30, 0x7, true), (45, 0x7, false),
+    (55, 0xB, true), (58, 0xB, false),
+];
+
+/// Chaque touche relâchée affiche un chiffre aléatoire
+const RANDOM_DIGITS: &str = "
+: main
+    v2 := 0
+    loop
+        v0 := key
+        v1 := random 0xF
+        i := hex v1
+        sprite v2 v3 5
+        v2 += 5
+    again
+";
+
+fn emulator(platform: &str, rom: &[u8]) -> Emulator {
+    let mut emulator = Emulator::new();
+    assert!(emulator.set_platform(platform));
+    assert!(emulator.load_rom(rom));
+    emulator.start();
+    emulator
+}
+
+/// Enregistrer `frames` frames avec des touches scriptées
+fn record(emulator: &mut Emulator, frames: u32, keys: &[(u32, u8, bool)]) -> Vec<u8> {
+    emulator.start_recording().unwrap();
+    for frame in 0..frames {
+        for &(_, key, pressed) in keys.iter().filter(|&&(at, _, _)| at == frame) {
+            if pressed {
+                emulator.key_down(key);
+            } else {
+                emulator.key_up(key);
+            }
+        }
+        emulator.run_frame(CYCLES_PER_FRAME);
+    }
+    emulator.stop_recording()
+}
+
+/// Rejouer un film jusqu'au bout, avec une vitesse de l'hôte différente
+fn play(emulator: &mut Emulator, movie: &[u8]) {
+    emulator.play_movie(movie).unwrap();
+    for _ in 0..emulator.get_movie_length() {
+        emulator.run_frame(7);
+    }
+    assert!(!emulator.is_playing_movie());
+}
+
+#[test]
+fn records_frames_events_and_settings() {
+    let rom = assemble("keypad.8o");
+    let mut recorder = emulator("chip8", &rom);
+    recorder.set_seed(42);
+    let data = record(&mut recorder, 70, &KEYPAD_SCRIPT);
+    assert!(!recorder.is_recording());
+    
+    let movie = Movie::from_bytes(&data).unwrap();
+    assert_eq!(movie.frames, 70);
+    assert_eq!(movie.header.seed, 42);
+    assert_eq!(movie.header.rom_size as usize, rom.len());
+    assert_eq!(movie.events.len(), 9);
+    assert_eq!(movie.events[0].input, MovieInput::Cycles(CYCLES_PER_FRAME));
+    assert_eq!(movie.events[1].frame, 10);
+    assert_eq!(movie.events[1].input, MovieInput::KeyDown(0x5));
+    assert_eq!(movie.events[8].input, MovieInput::KeyUp(0xB));
+    assert_eq!(Movie::from_bytes(&movie.to_bytes()), Ok(movie));
+    
+    assert!(Movie::from_bytes(&data[..data.len() - 1]).is_err());
+    assert!(Movie::from_bytes(b"F8TR").is_err());
+}
+
+#[test]
+fn replay_is_bit_exact() {
+    let rom = assemble("keypad.8o");
+    let mut recorder = emulator("chip8", &rom);
+    let movie = record(&mut recorder, 70, &KEYPAD_SCRIPT);
+    assert_eq!(screen_hash(recorder.cpu()), 0xD04F_F07F_CFA7_D6A4);
+    
+    // Plateforme, quirks et vitesse viennent du film
+    let mut player = emulator("xo-chip", &rom);
+    player.run_frame(CYCLES_PER_FRAME);
+    play(&mut player, &movie);
+    assert_eq!(player.save_state(), recorder.save_state());
+}
+
+#[test]
+fn replay_restores_the_random_seed() {
+    let rom = assembler::assemble(RANDOM_DIGITS).unwrap().rom;
+    let keys: Vec<(u32, u8, bool)> = (0..8).flat_map(|n| [(n * 10 + 5, 0x1, true), (n * 10 + 8, 0x1, false)]).collect();
+    let mut recorder = emulator("chip8", &rom);
+    assert!(recorder.set_rng("xorshift"));
+    recorder.set_seed(1234);
+    let movie = record(&mut recorder, 90, &keys);
+    
+    let mut player = emulator("chip8", &rom);
+    player.set_seed(1);
+    play(&mut player, &movie);
+    assert_eq!(screen_hash(player.cpu()), screen_hash(recorder.cpu()));
+    
+    // Même script sans le film : autre graine, autres chiffres
+    let mut live = emulator("chip8", &rom);
+    live.set_seed(1);
+    record(&mut live, 90, &keys);
+    assert_ne!(screen_hash(live.cpu()), screen_hash(recorder.cpu()));
+}
+
+#[test]
+fn host_keys_are_ignored_during_playback() {
+    let rom = assemble("keypad.8o");
+    let mut recorder = emulator("chip8", &rom);
+    let movie = record(&mut recorder, 70, &KEYPAD_SCRIPT);
+    
+    let mut player = emulator("chip8", &rom);
+    player.play_movie(&movie).unwrap();
+    for frame in 0..70 {
+        player.key_down(frame as u8 % 16);
+        player.run_frame(CYCLES_PER_FRAME);
+        player.key_up(frame as u8 % 16);
+    }
+    assert_eq!(screen_hash(player.cpu()), screen_hash(recorder.cpu()));
+}
+
+#[test]
+fn movie_needs_the_same_rom() {
+    let rom = assemble("keypad.8o");
+    let mut recorder = emulator("chip8", &rom);
+    let movie = record(&mut recorder, 10, &[]);
+    
+    let mut player = emulator("chip8", &assemble("flags.8o"));
+    assert!(player.play_movie(&movie).is_err());
+    assert!(!player.is_playing_movie());
+    
+    // Changer de ROM abandonne l'enregistrement
+    recorder.start_recording().unwrap();
+    assert!(recorder.load_rom(&rom));
+    assert!(!recorder.is_recording());
+    assert!(recorder.stop_recording().is_empty());
+}
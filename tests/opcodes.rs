@@ -0,0 +1,616 @@
+//! Un test par gestionnaire `execute_*` du CPU (et par variante de quirk)
+
+mod common;
+
+use common::{chip8, run_frames, schip, step, xo_chip, START};
+use ferris8::{CpuErrorKind, Emulator, ExecutionPolicy, HaltReason, Quirks};
+
+// ========== 0xxx ==========
+
+#[test]
+fn cls_00e0_clears_screen() {
+    let mut cpu = chip8(&[0x00E0]);
+    cpu.display.set_pixel(3, 4, true);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.display.count_active_pixels(), 0);
+    assert!(cpu.draw_flag);
+}
+
+#[test]
+fn call_2nnn_and_ret_00ee() {
+    let mut cpu = chip8(&[0x2206, 0x6001, 0x0000, 0x00EE]);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.pc, 0x206);
+    assert_eq!(cpu.call_stack(), &[0x202]);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.v[0], 1);
+    assert_eq!(cpu.sp, 0);
+}
+
+#[test]
+fn ret_00ee_with_empty_stack_halts() {
+    let mut cpu = chip8(&[0x00EE]);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.halt_reason, Some(HaltReason::StackUnderflow));
+    assert_eq!(cpu.errors().next().map(|e| e.kind), Some(CpuErrorKind::StackUnderflow));
+}
+
+#[test]
+fn opcode_0000_halts() {
+    let mut cpu = chip8(&[0x0000]);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.halt_reason, Some(HaltReason::Opcode0000));
+    assert_eq!(cpu.cycle_count, 1);
+}
+
+#[test]
+fn sys_0nnn_is_ignored() {
+    let mut cpu = chip8(&[0x0123, 0x6001]);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.v[0], 1);
+    assert_eq!(cpu.error_count, 0);
+}
+
+#[test]
+fn schip_exit_00fd() {
+    let mut cpu = schip(&[0x00FD]);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.halt_reason, Some(HaltReason::Exit00FD));
+}
+
+#[test]
+fn schip_resolution_00fe_00ff() {
+    let mut cpu = schip(&[0x00FF, 0x00FE]);
+    step(&mut cpu, 1);
+    assert_eq!((cpu.display.width(), cpu.display.height()), (128, 64));
+    step(&mut cpu, 1);
+    assert_eq!((cpu.display.width(), cpu.display.height()), (64, 32));
+}
+
+#[test]
+fn schip_scroll_down_00cn() {
+    let mut cpu = schip(&[0x00C3]);
+    cpu.display.set_pixel(5, 0, true);
+    step(&mut cpu, 1);
+    assert!(cpu.display.get_pixel(5, 3));
+    assert!(!cpu.display.get_pixel(5, 0));
+}
+
+#[test]
+fn schip_scroll_right_00fb_and_left_00fc() {
+    let mut cpu = schip(&[0x00FB, 0x00FC, 0x00FC]);
+    cpu.display.set_pixel(4, 2, true);
+    step(&mut cpu, 1);
+    assert!(cpu.display.get_pixel(8, 2));
+    step(&mut cpu, 2);
+    assert!(cpu.display.get_pixel(0, 2));
+    assert_eq!(cpu.display.count_active_pixels(), 1);
+}
+
+#[test]
+fn xo_scroll_up_00dn() {
+    let mut cpu = xo_chip(&[0x00D2]);
+    cpu.display.set_pixel(1, 5, true);
+    step(&mut cpu, 1);
+    assert!(cpu.display.get_pixel(1, 3));
+}
+
+#[test]
+fn schip_opcodes_are_sys_calls_on_chip8() {
+    let mut cpu = chip8(&[0x00FF, 0x00FD, 0x6001]);
+    step(&mut cpu, 3);
+    assert_eq!(cpu.display.width(), 64);
+    assert_eq!(cpu.v[0], 1);
+    assert!(!cpu.is_halted());
+}
+
+// ========== Sauts ==========
+
+#[test]
+fn jump_1nnn() {
+    let mut cpu = chip8(&[0x1206, 0x6001, 0x6002, 0x6003]);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.v[0], 3);
+}
+
+#[test]
+fn jump_1nnn_to_odd_address_is_an_error() {
+    let mut cpu = chip8(&[0x1203, 0x6001]);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.v[0], 1);
+    assert_eq!(cpu.errors().next().map(|e| (e.pc, e.opcode, e.kind)),
+               Some((START, 0x1203, CpuErrorKind::InvalidJump { target: 0x203 })));
+}
+
+#[test]
+fn stack_overflow_2nnn() {
+    let mut cpu = chip8(&[0x2200]);
+    cpu.set_stack_depth(4);
+    step(&mut cpu, 5);
+    assert_eq!(cpu.sp, 4);
+    assert_eq!(cpu.errors().map(|e| e.kind).collect::<Vec<_>>(), vec![CpuErrorKind::StackOverflow]);
+}
+
+#[test]
+fn stack_depth_follows_platform() {
+    assert_eq!(chip8(&[0x0000]).stack_depth(), 12);
+    assert_eq!(schip(&[0x0000]).stack_depth(), 16);
+    assert_eq!(xo_chip(&[0x0000]).stack_depth(), 64);
+}
+
+#[test]
+fn jump_bnnn_uses_v0() {
+    let mut cpu = chip8(&[0x6004, 0x6102, 0xB204, 0x0000, 0x6201]);
+    step(&mut cpu, 4);
+    assert_eq!(cpu.v[2], 1);
+}
+
+#[test]
+fn jump_bxnn_quirk_uses_vx() {
+    let mut cpu = chip8(&[0x6004, 0x6202, 0xB206, 0x0000, 0x6301]);
+    cpu.quirks.jump_vx = true;
+    step(&mut cpu, 4);
+    assert_eq!(cpu.v[3], 1);
+}
+
+// ========== Sauts conditionnels ==========
+
+#[test]
+fn skip_3xkk() {
+    let mut cpu = chip8(&[0x6A12, 0x3A12, 0x6001, 0x3A13, 0x6102]);
+    step(&mut cpu, 4);
+    assert_eq!((cpu.v[0], cpu.v[1]), (0, 2));
+}
+
+#[test]
+fn skip_4xkk() {
+    let mut cpu = chip8(&[0x6A12, 0x4A13, 0x6001, 0x4A12, 0x6102]);
+    step(&mut cpu, 4);
+    assert_eq!((cpu.v[0], cpu.v[1]), (0, 2));
+}
+
+#[test]
+fn skip_5xy0() {
+    let mut cpu = chip8(&[0x6107, 0x6207, 0x5120, 0x6001, 0x6303]);
+    step(&mut cpu, 4);
+    assert_eq!((cpu.v[0], cpu.v[3]), (0, 3));
+}
+
+#[test]
+fn skip_9xy0() {
+    let mut cpu = chip8(&[0x6107, 0x6208, 0x9120, 0x6001, 0x6303]);
+    step(&mut cpu, 4);
+    assert_eq!((cpu.v[0], cpu.v[3]), (0, 3));
+}
+
+#[test]
+fn skip_over_long_f000_on_xo_chip() {
+    let mut cpu = xo_chip(&[0x3000, 0xF000, 0x0300, 0x6101]);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.v[1], 1);
+    assert_eq!(cpu.i, 0);
+}
+
+#[test]
+fn unknown_5xyn_is_an_error() {
+    let mut cpu = chip8(&[0x5121]);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.errors().next().map(|e| e.kind), Some(CpuErrorKind::UnknownOpcode));
+}
+
+// ========== Registres ==========
+
+#[test]
+fn load_6xkk() {
+    let mut cpu = chip8(&[0x6EAB]);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.v[0xE], 0xAB);
+}
+
+#[test]
+fn add_7xkk_wraps_without_flag() {
+    let mut cpu = chip8(&[0x63FF, 0x7302]);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.v[3], 1);
+    assert_eq!(cpu.v[0xF], 0);
+}
+
+#[test]
+fn logic_8xy0_to_8xy3() {
+    let mut cpu = chip8(&[0x610C, 0x620A, 0x8310, 0x8321, 0x6F05, 0x8122]);
+    step(&mut cpu, 6);
+    assert_eq!(cpu.v[3], 0x0E);
+    assert_eq!(cpu.v[1], 0x08);
+    assert_eq!(cpu.v[0xF], 0, "vf_reset remet VF à 0");
+    
+    let mut cpu = chip8(&[0x610C, 0x620A, 0x8123]);
+    cpu.quirks.vf_reset = false;
+    cpu.v[0xF] = 7;
+    step(&mut cpu, 3);
+    assert_eq!(cpu.v[1], 0x06);
+    assert_eq!(cpu.v[0xF], 7);
+}
+
+#[test]
+fn add_8xy4_sets_carry() {
+    let mut cpu = chip8(&[0x61F0, 0x6220, 0x8124, 0x6301, 0x8134]);
+    step(&mut cpu, 3);
+    assert_eq!((cpu.v[1], cpu.v[0xF]), (0x10, 1));
+    step(&mut cpu, 2);
+    assert_eq!((cpu.v[1], cpu.v[0xF]), (0x11, 0));
+}
+
+#[test]
+fn sub_8xy5_and_subn_8xy7_set_not_borrow() {
+    let mut cpu = chip8(&[0x6105, 0x6203, 0x8125, 0x6305, 0x6403, 0x8347]);
+    step(&mut cpu, 3);
+    assert_eq!((cpu.v[1], cpu.v[0xF]), (2, 1));
+    step(&mut cpu, 3);
+    assert_eq!((cpu.v[3], cpu.v[0xF]), (0xFE, 0));
+}
+
+#[test]
+fn arithmetic_flag_wins_over_result_in_vf() {
+    // 8FE4, 8FE5, 8FE7 : VF reçoit le flag, pas le résultat
+    let mut cpu = chip8(&[0x6FF0, 0x6E20, 0x8FE4]);
+    step(&mut cpu, 3);
+    assert_eq!(cpu.v[0xF], 1);
+    
+    let mut cpu = chip8(&[0x6F01, 0x6E02, 0x8FE5]);
+    step(&mut cpu, 3);
+    assert_eq!(cpu.v[0xF], 0);
+    
+    let mut cpu = chip8(&[0x6F01, 0x6E03, 0x8FE7]);
+    step(&mut cpu, 3);
+    assert_eq!(cpu.v[0xF], 1);
+}
+
+#[test]
+fn shift_8xy6_and_8xye_use_vy_on_vip() {
+    let mut cpu = chip8(&[0x6281, 0x8126, 0x832E]);
+    step(&mut cpu, 2);
+    assert_eq!((cpu.v[1], cpu.v[0xF]), (0x40, 1));
+    step(&mut cpu, 1);
+    assert_eq!((cpu.v[3], cpu.v[0xF]), (0x02, 1));
+}
+
+#[test]
+fn shift_quirk_shifts_vx_in_place() {
+    let mut cpu = chip8(&[0x6102, 0x62FF, 0x8126, 0x6F81, 0x8F1E]);
+    cpu.quirks.shift = true;
+    step(&mut cpu, 3);
+    assert_eq!((cpu.v[1], cpu.v[0xF]), (0x01, 0));
+    step(&mut cpu, 2);
+    assert_eq!(cpu.v[0xF], 1, "VF reçoit le bit sorti, pas le résultat");
+}
+
+#[test]
+fn unknown_8xyn_is_an_error() {
+    let mut cpu = chip8(&[0x8128]);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.error_count, 1);
+}
+
+// ========== I et mémoire ==========
+
+#[test]
+fn load_annn() {
+    let mut cpu = chip8(&[0xA123]);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.i, 0x123);
+}
+
+#[test]
+fn random_cxkk_is_masked_and_reproducible() {
+    let program = [0xC10F, 0xC2F0, 0xC300];
+    let mut first = chip8(&program);
+    let mut second = chip8(&program);
+    step(&mut first, 3);
+    step(&mut second, 3);
+    assert_eq!(first.v[1] & 0xF0, 0);
+    assert_eq!(first.v[2] & 0x0F, 0);
+    assert_eq!(first.v[3], 0);
+    assert_eq!(first.v, second.v);
+}
+
+#[test]
+fn add_i_fx1e() {
+    let mut cpu = chip8(&[0xA0FF, 0x6102, 0xF11E]);
+    step(&mut cpu, 3);
+    assert_eq!(cpu.i, 0x101);
+}
+
+#[test]
+fn font_fx29_and_big_font_fx30() {
+    let mut cpu = schip(&[0x610A, 0xF129, 0xF130]);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.i, 0x50 + 10 * 5);
+    assert_eq!(cpu.memory.peek_byte(cpu.i), 0xF0);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.i, 0xA0 + 10 * 10);
+}
+
+#[test]
+fn bcd_fx33() {
+    let mut cpu = chip8(&[0x61FE, 0xA300, 0xF133]);
+    step(&mut cpu, 3);
+    let digits: Vec<u8> = (0..3).map(|offset| cpu.memory.peek_byte(0x300 + offset)).collect();
+    assert_eq!(digits, vec![2, 5, 4]);
+}
+
+#[test]
+fn store_fx55_and_load_fx65_increment_i_on_vip() {
+    let mut cpu = chip8(&[0x6011, 0x6122, 0x6233, 0xA300, 0xF255, 0xA300, 0xF165]);
+    step(&mut cpu, 5);
+    assert_eq!(cpu.i, 0x303);
+    assert_eq!(cpu.memory.peek_byte(0x302), 0x33);
+    cpu.v = [0; 16];
+    step(&mut cpu, 2);
+    assert_eq!(&cpu.v[..3], &[0x11, 0x22, 0]);
+    assert_eq!(cpu.i, 0x302);
+}
+
+#[test]
+fn store_fx55_keeps_i_without_quirk() {
+    let mut cpu = chip8(&[0xA300, 0xF355]);
+    cpu.quirks.memory_increment = false;
+    step(&mut cpu, 2);
+    assert_eq!(cpu.i, 0x300);
+}
+
+#[test]
+fn store_fx55_past_memory_end_is_an_error() {
+    let mut cpu = chip8(&[0xAFFE, 0xF355]);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.errors().next().map(|e| e.kind),
+               Some(CpuErrorKind::MemoryOutOfBounds { address: 0xFFE, length: 4 }));
+}
+
+#[test]
+fn font_area_is_write_protected_unless_accurate() {
+    let mut cpu = chip8(&[0xA050, 0x60AA, 0xF055]);
+    step(&mut cpu, 3);
+    assert_eq!(cpu.memory.peek_byte(0x50), 0xF0);
+    
+    let mut cpu = chip8(&[0xA050, 0x60AA, 0xF055]);
+    cpu.set_policy(ExecutionPolicy::Accurate);
+    step(&mut cpu, 3);
+    assert_eq!(cpu.memory.peek_byte(0x50), 0xAA);
+}
+
+#[test]
+fn xo_save_5xy2_and_load_5xy3_ranges() {
+    let mut cpu = xo_chip(&[0x6101, 0x6202, 0x6303, 0xA400, 0x5312, 0x5133]);
+    step(&mut cpu, 5);
+    let saved: Vec<u8> = (0..3).map(|offset| cpu.memory.peek_byte(0x400 + offset)).collect();
+    assert_eq!(saved, vec![3, 2, 1], "x > y : ordre décroissant");
+    assert_eq!(cpu.i, 0x400, "I ne bouge pas");
+    cpu.v = [0; 16];
+    step(&mut cpu, 1);
+    assert_eq!(&cpu.v[1..4], &[3, 2, 1]);
+}
+
+#[test]
+fn xo_long_load_f000() {
+    let mut cpu = xo_chip(&[0xF000, 0xBEEF, 0x6101]);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.i, 0xBEEF);
+    assert_eq!(cpu.v[1], 1);
+}
+
+#[test]
+fn rpl_fx75_and_fx85() {
+    let mut cpu = schip(&[0x6007, 0x6108, 0xF175, 0x6000, 0x6100, 0xF185]);
+    step(&mut cpu, 6);
+    assert_eq!(&cpu.v[..2], &[7, 8]);
+    
+    let mut cpu = schip(&[0xF875]);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.errors().next().map(|e| e.kind), Some(CpuErrorKind::InvalidRegister { register: 8 }));
+}
+
+// ========== Affichage ==========
+
+#[test]
+fn draw_dxyn_sets_collision() {
+    let mut cpu = chip8(&[0xA050, 0x6002, 0x6103, 0xD015, 0xD015]);
+    step(&mut cpu, 4);
+    assert!(cpu.display.get_pixel(2, 3));
+    assert_eq!(cpu.display.count_active_pixels(), 14);
+    assert_eq!(cpu.v[0xF], 0);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.display.count_active_pixels(), 0);
+    assert_eq!(cpu.v[0xF], 1);
+}
+
+#[test]
+fn draw_dxyn_clips_or_wraps() {
+    let program = [0xA050, 0x603E, 0x6100, 0xD015];
+    let mut cpu = chip8(&program);
+    step(&mut cpu, 4);
+    assert_eq!(cpu.display.count_active_pixels(), 7, "coupé au bord");
+    
+    let mut cpu = chip8(&program);
+    cpu.quirks.clip = false;
+    step(&mut cpu, 4);
+    assert_eq!(cpu.display.count_active_pixels(), 14, "boucle à gauche");
+    assert!(cpu.display.get_pixel(0, 0));
+}
+
+#[test]
+fn draw_waits_for_vblank_with_display_wait() {
+    let mut cpu = chip8(&[0xA050, 0xD005, 0xD005]);
+    cpu.quirks = Quirks::vip();
+    step(&mut cpu, 3);
+    assert_eq!(cpu.display.count_active_pixels(), 14, "le second sprite attend la frame suivante");
+    cpu.tick_timers();
+    step(&mut cpu, 1);
+    assert_eq!(cpu.display.count_active_pixels(), 0);
+}
+
+#[test]
+fn display_wait_at_the_end_of_memory_wraps_pc() {
+    let mut cpu = xo_chip(&[0xD005]);
+    cpu.quirks.display_wait = true;
+    cpu.set_policy(ExecutionPolicy::Accurate);
+    step(&mut cpu, 1);
+    // Le second Dxyn, en fin de mémoire, attend la frame suivante
+    cpu.memory.write_byte(0xFFFE, 0xD0);
+    cpu.memory.write_byte(0xFFFF, 0x05);
+    cpu.pc = 0xFFFE;
+    step(&mut cpu, 2);
+    assert_eq!(cpu.pc, 0xFFFE);
+}
+
+#[test]
+fn schip_draw_16x16_dxy0() {
+    let mut cpu = schip(&[0x00FF, 0xA300, 0xD000]);
+    cpu.memory.write_bytes(0x300, &[0xFF; 32]);
+    step(&mut cpu, 3);
+    assert_eq!(cpu.display.count_active_pixels(), 256);
+}
+
+#[test]
+fn xo_plane_fn01_draws_on_second_plane() {
+    let mut cpu = xo_chip(&[0xF201, 0xA050, 0xD005]);
+    step(&mut cpu, 3);
+    assert_eq!(cpu.display.get_plane_pixel(0, 0), 2);
+}
+
+// ========== Clavier et timers ==========
+
+#[test]
+fn key_skips_ex9e_and_exa1() {
+    let mut cpu = chip8(&[0x6005, 0xE09E, 0x6101, 0xE0A1, 0x6202]);
+    cpu.key_down(5);
+    step(&mut cpu, 4);
+    assert_eq!((cpu.v[1], cpu.v[2]), (0, 2));
+}
+
+#[test]
+fn wait_key_fx0a_blocks_until_a_key_is_released() {
+    let mut cpu = chip8(&[0xF30A, 0x6101]);
+    step(&mut cpu, 5);
+    assert_eq!(cpu.pc, START);
+    cpu.key_down(0xB);
+    step(&mut cpu, 5);
+    assert_eq!(cpu.pc, START);
+    cpu.key_up(0xB);
+    step(&mut cpu, 2);
+    assert_eq!((cpu.v[3], cpu.v[1]), (0xB, 1));
+}
+
+#[test]
+fn wait_key_fx0a_ignores_earlier_presses() {
+    let mut cpu = chip8(&[0xF30A]);
+    cpu.key_down(0x4);
+    cpu.key_up(0x4);
+    step(&mut cpu, 3);
+    assert_eq!(cpu.pc, START);
+    
+    // Touche tenue avant Fx0A : son relâchement suffit
+    cpu.key_down(0x6);
+    step(&mut cpu, 1);
+    cpu.key_up(0x6);
+    step(&mut cpu, 1);
+    assert_eq!((cpu.v[3], cpu.pc), (0x6, START + 2));
+}
+
+#[test]
+fn wait_key_fx0a_at_the_end_of_memory_wraps_pc() {
+    let mut cpu = xo_chip(&[0x00E0]);
+    cpu.set_policy(ExecutionPolicy::Accurate);
+    cpu.memory.write_byte(0xFFFE, 0xF3);
+    cpu.memory.write_byte(0xFFFF, 0x0A);
+    cpu.pc = 0xFFFE;
+    step(&mut cpu, 3);
+    assert_eq!(cpu.pc, 0xFFFE);
+}
+
+#[test]
+fn wait_key_fx0a_keeps_timers_running() {
+    let mut cpu = chip8(&[0x6003, 0xF015, 0xF00A]);
+    run_frames(&mut cpu, 2, 10, &[]);
+    assert_eq!((cpu.delay_timer, cpu.pc), (1, START + 4));
+}
+
+#[test]
+fn wait_key_fx0a_key_press_quirk() {
+    let mut cpu = chip8(&[0xF20A]);
+    cpu.quirks.key_press = true;
+    step(&mut cpu, 1);
+    cpu.key_down(0x9);
+    step(&mut cpu, 1);
+    assert_eq!((cpu.v[2], cpu.pc), (0x9, START + 2));
+}
+
+#[test]
+fn timers_fx07_fx15_fx18() {
+    let mut cpu = chip8(&[0x6005, 0xF015, 0xF018, 0xF107]);
+    step(&mut cpu, 3);
+    assert_eq!((cpu.delay_timer, cpu.sound_timer), (5, 5));
+    cpu.tick_timers();
+    cpu.tick_timers();
+    step(&mut cpu, 1);
+    assert_eq!(cpu.v[1], 3);
+    assert_eq!(cpu.sound_timer, 3);
+}
+
+#[test]
+fn xo_audio_f002_and_pitch_fx3a() {
+    let mut cpu = xo_chip(&[0xA300, 0xF002, 0x6040, 0xF03A]);
+    step(&mut cpu, 4);
+    assert_eq!(cpu.error_count, 0);
+}
+
+#[test]
+fn unknown_exnn_and_fxnn_are_errors() {
+    let mut cpu = chip8(&[0xE0FF, 0xF0FF]);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.error_count, 2);
+}
+
+// ========== Politique d'exécution ==========
+
+#[test]
+fn strict_policy_traps_on_first_error() {
+    let mut cpu = chip8(&[0x1203, 0x6001]);
+    cpu.set_policy(ExecutionPolicy::Strict);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.halt_reason, Some(HaltReason::Trap));
+    assert_eq!(cpu.v[0], 0);
+}
+
+#[test]
+fn accurate_policy_allows_odd_jumps() {
+    // 1203 saute au milieu de 6001 : les octets 01 00 forment un SYS ignoré
+    let mut cpu = chip8(&[0x1203, 0x6001, 0x0000]);
+    cpu.set_policy(ExecutionPolicy::Accurate);
+    step(&mut cpu, 2);
+    assert_eq!(cpu.pc, 0x205);
+    assert_eq!(cpu.error_count, 0);
+}
+
+#[test]
+fn too_many_errors_halt_the_cpu() {
+    let mut cpu = chip8(&[0xE0FF, 0x1200]);
+    step(&mut cpu, 100);
+    assert_eq!(cpu.halt_reason, Some(HaltReason::ErrorLimit));
+    assert!(cpu.error_limit_reached());
+}
+
+#[test]
+fn emulator_exposes_typed_errors() {
+    let mut emulator = Emulator::new();
+    assert!(emulator.set_platform("chip8"));
+    assert!(emulator.load_rom(&[0xE0, 0xFF, 0x12, 0x00]));
+    emulator.start();
+    emulator.run_frame(2);
+    
+    let error = emulator.errors()[0];
+    assert_eq!((error.pc, error.opcode, error.kind), (START, 0xE0FF, CpuErrorKind::UnknownOpcode));
+    assert_eq!(emulator.get_error_kinds()[0], "unknown_opcode");
+    assert_eq!(emulator.get_error_pcs()[0], START);
+    assert_eq!(emulator.get_error_opcodes()[0], 0xE0FF);
+    assert!(emulator.describe_errors().starts_with("Opcode inconnu [E0FF]"));
+}
@@ -0,0 +1,113 @@
+//! Trace d'exécution : comparaison avec la trace d'un autre émulateur
+
+use ferris8::Emulator;
+
+/// Attendre une touche, la copier dans V1 puis boucler sur place
+const WAIT_KEY: [u8; 6] = [0xF3, 0x0A, 0x81, 0x30, 0x12, 0x04];
+
+/// Cinq chargements puis 0000, qui arrête le CPU
+const LOADS: [u8; 12] = [0x60, 0x01, 0x61, 0x02, 0x62, 0x03, 0x63, 0x04, 0x64, 0x05, 0x00, 0x00];
+
+const LOADS_TRACE: &str = "\
+1 0200: 6001 I=0000 V0=01
+2 0202: 6102 I=0000 V1=02
+3 0204: 6203 I=0000 V2=03
+4 0206: 6304 I=0000 V3=04
+5 0208: 6405 I=0000 V4=05
+6 020A: 0000 I=0000
+";
+
+/// Émulateur Chip-8 qui trace les `capacity` dernières instructions de `rom`
+fn traced(rom: &[u8], capacity: usize) -> Emulator {
+    let mut emulator = Emulator::new();
+    assert!(emulator.set_platform("chip8"));
+    emulator.set_rom_database_enabled(false);
+    assert!(emulator.load_rom(rom));
+    emulator.set_trace(capacity);
+    emulator.start();
+    emulator
+}
+
+fn traced_run() -> Emulator {
+    let mut emulator = traced(&WAIT_KEY, 1000);
+    emulator.run_frame(10);
+    emulator.key_down(0xB);
+    emulator.run_frame(10);
+    emulator.key_up(0xB);
+    emulator.run_frame(10);
+    emulator
+}
+
+#[test]
+fn waiting_cycles_are_not_compared() {
+    let emulator = traced_run();
+    assert!(emulator.trace_length() > 20);
+    
+    // Référence qui compte Fx0A et la boucle finale une seule fois
+    let reference = "1 0200: F30A I=0000 V3=0B\n2 0202: 8130 I=0000 V1=0B\n3 0204: 1204 I=0000\n";
+    assert_eq!(emulator.diff_trace(reference.as_bytes()), Ok(None));
+    
+    let wrong = reference.replace("V1=0B", "V1=0C");
+    let divergence = emulator.diff_trace(wrong.as_bytes()).unwrap().unwrap();
+    assert!(divergence.contains("V1 0B au lieu de 0C"), "{}", divergence);
+}
+
+#[test]
+fn binary_export_matches_itself() {
+    let emulator = traced_run();
+    assert_eq!(emulator.diff_trace(&emulator.export_trace()), Ok(None));
+    assert_eq!(emulator.diff_trace(emulator.export_trace_text(0).as_bytes()), Ok(None));
+}
+
+#[test]
+fn traces_of_different_lengths_diverge() {
+    let emulator = traced_run();
+    let reference = "1 0200: F30A I=0000 V3=0B\n2 0202: 8130 I=0000 V1=0B\n3 0204: 1204 I=0000\n";
+    
+    let longer = format!("{}4 0206: 6001 I=0000 V0=01\n", reference);
+    let divergence = emulator.diff_trace(longer.as_bytes()).unwrap().unwrap();
+    assert!(divergence.contains("trace terminée, la référence continue"), "{}", divergence);
+    assert!(divergence.contains("attendu: 4 0206: 6001"), "{}", divergence);
+    
+    let shorter = reference.lines().take(2).collect::<Vec<_>>().join("\n");
+    let divergence = emulator.diff_trace(shorter.as_bytes()).unwrap().unwrap();
+    assert!(divergence.contains("référence terminée"), "{}", divergence);
+}
+
+#[test]
+fn halted_cpu_diverges_from_a_longer_reference() {
+    // 00EE avec une pile vide arrête le CPU ; la référence a continué
+    let mut emulator = traced(&[0x60, 0x01, 0x00, 0xEE, 0x61, 0x02], 100);
+    emulator.run_frame(10);
+    assert!(emulator.is_halted());
+    
+    let reference = "1 0200: 6001 I=0000 V0=01\n2 0202: 00EE I=0000\n3 0204: 6102 I=0000 V1=02\n";
+    let divergence = emulator.diff_trace(reference.as_bytes()).unwrap().unwrap();
+    assert!(divergence.contains("trace terminée"), "{}", divergence);
+    assert!(divergence.contains("attendu: 3 0204: 6102"), "{}", divergence);
+}
+
+#[test]
+fn wrapped_buffer_aligns_on_its_oldest_instruction() {
+    // Seules les trois dernières instructions sont gardées
+    let mut emulator = traced(&LOADS, 3);
+    emulator.run_frame(10);
+    assert_eq!(emulator.trace_length(), 3);
+    assert_eq!(emulator.diff_trace(LOADS_TRACE.as_bytes()), Ok(None));
+    
+    let wrong = LOADS_TRACE.replace("V4=05", "V4=06");
+    let divergence = emulator.diff_trace(wrong.as_bytes()).unwrap().unwrap();
+    assert!(divergence.contains("au cycle 5"), "{}", divergence);
+    
+    // La référence ne contient plus notre plus ancienne instruction
+    let late = LOADS_TRACE.lines().take(3).collect::<Vec<_>>().join("\n");
+    assert!(emulator.diff_trace(late.as_bytes()).unwrap_err().contains("ne couvre plus"));
+}
+
+#[test]
+fn later_reference_aligns_on_its_first_instruction() {
+    let mut emulator = traced(&LOADS, 100);
+    emulator.run_frame(10);
+    let late: String = LOADS_TRACE.lines().skip(2).map(|line| format!("{}\n", line)).collect();
+    assert_eq!(emulator.diff_trace(late.as_bytes()), Ok(None));
+}
@@ -0,0 +1,57 @@
+// 🔊 Ferris-8 - AudioWorklet qui joue les échantillons PCM synthétisés par Rust
+
+// Latence maximale tolérée avant de jeter les vieux échantillons (en secondes)
+const MAX_LATENCY = 0.1;
+
+class Ferris8AudioProcessor extends AudioWorkletProcessor {
+    constructor() {
+        super();
+        this.queue = [];
+        this.offset = 0;
+        this.queued = 0;
+
+        // Chaque message contient un Float32Array d'échantillons mono
+        this.port.onmessage = (event) => {
+            this.queue.push(event.data);
+            this.queued += event.data.length;
+
+            // Éviter que la latence ne grandisse si le rendu va plus vite que l'audio
+            const maxQueued = sampleRate * MAX_LATENCY;
+            while (this.queued - this.offset > maxQueued && this.queue.length > 1) {
+                this.queued -= this.queue[0].length;
+                this.queue.shift();
+                this.offset = 0;
+            }
+        };
+    }
+
+    process(inputs, outputs) {
+        const output = outputs[0];
+        const channel = output[0];
+
+        for (let i = 0; i < channel.length; i++) {
+            if (this.queue.length === 0) {
+                channel[i] = 0; // Sous-alimentation : silence
+                continue;
+            }
+
+            const chunk = this.queue[0];
+            channel[i] = chunk[this.offset++];
+
+            if (this.offset >= chunk.length) {
+                this.queue.shift();
+                this.queued -= chunk.length;
+                this.offset = 0;
+            }
+        }
+
+        // Recopier sur les autres canaux (stéréo)
+        for (let c = 1; c < output.length; c++) {
+            output[c].set(channel);
+        }
+
+        return true;
+    }
+}
+
+registerProcessor('ferris8-audio', Ferris8AudioProcessor);
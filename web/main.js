@@ -1,13 +1,12 @@
 // 🦀 Ferris-8 - Interface JavaScript complète et sécurisée
 
-import init, { Emulator, greet } from './pkg/ferris8.js';
+import init, { Emulator, BreakKind, HaltReason, greet } from './pkg/ferris8.js';
 
-// Système audio Web Audio API
+// Système audio : les échantillons sont synthétisés par Rust et joués par un AudioWorklet
 class AudioSystem {
     constructor() {
         this.audioContext = null;
-        this.oscillator = null;
-        this.gainNode = null;
+        this.node = null;
         this.enabled = true;
         this.initialized = false;
     }
@@ -15,8 +14,11 @@ class AudioSystem {
     async init() {
         try {
             this.audioContext = new (window.AudioContext || window.webkitAudioContext)();
-            this.gainNode = this.audioContext.createGain();
-            this.gainNode.connect(this.audioContext.destination);
+            await this.audioContext.audioWorklet.addModule('./audio-worklet.js');
+            this.node = new AudioWorkletNode(this.audioContext, 'ferris8-audio', {
+                outputChannelCount: [2]
+            });
+            this.node.connect(this.audioContext.destination);
             this.initialized = true;
             console.log('🔊 Audio system initialized');
         } catch (error) {
@@ -25,61 +27,83 @@ class AudioSystem {
         }
     }
 
-    playBeep(frequency = 440, volume = 0.5) {
-        if (!this.enabled || !this.initialized) return;
-
-        try {
-            // Stop current beep if any
-            this.stopBeep();
-
-            // Resume context if suspended (required by browser policies)
-            if (this.audioContext.state === 'suspended') {
-                this.audioContext.resume();
-            }
+    get sampleRate() {
+        return this.initialized ? this.audioContext.sampleRate : 0;
+    }
 
-            // Create oscillator
-            this.oscillator = this.audioContext.createOscillator();
-            this.oscillator.type = 'square';
-            this.oscillator.frequency.setValueAtTime(frequency, this.audioContext.currentTime);
-            
-            // Set volume
-            this.gainNode.gain.setValueAtTime(volume * 0.1, this.audioContext.currentTime); // Lower volume
-            
-            // Connect and start
-            this.oscillator.connect(this.gainNode);
-            this.oscillator.start();
-        } catch (error) {
-            console.warn('⚠️ Audio playback error:', error);
+    // Reprendre le contexte (bloqué tant qu'il n'y a pas eu d'interaction utilisateur)
+    resume() {
+        if (this.initialized && this.audioContext.state === 'suspended') {
+            this.audioContext.resume();
         }
     }
 
-    stopBeep() {
-        if (this.oscillator) {
-            try {
-                this.oscillator.stop();
-                this.oscillator.disconnect();
-            } catch (error) {
-                // Oscillator might already be stopped
-            }
-            this.oscillator = null;
+    // Envoyer les échantillons en attente de l'émulateur vers le worklet
+    pump(emulator) {
+        if (!this.initialized) return;
+
+        const available = emulator.audio_samples_available();
+        if (available === 0) return;
+
+        const samples = new Float32Array(available);
+        emulator.fill_audio_buffer(samples);
+
+        if (this.enabled) {
+            this.node.port.postMessage(samples, [samples.buffer]);
         }
     }
 
     setEnabled(enabled) {
         this.enabled = enabled;
-        if (!enabled) {
-            this.stopBeep();
-        }
     }
 }
 
 // Global audio system
 const audioSystem = new AudioSystem();
-window.ferris8Audio = {
-    playBeep: (frequency, volume) => audioSystem.playBeep(frequency, volume),
-    stopBeep: () => audioSystem.stopBeep()
+
+// Durée d'une frame Chip-8 (timers à 60 Hz)
+const FRAME_DURATION = 1000 / 60;
+
+// Listing du debugger : 16 instructions dont 5 avant le PC
+const DISASM_LINES = 16;
+const DISASM_LINES_BEFORE_PC = 5;
+
+// Retour arrière : une photo toutes les 2 frames, 10 secondes d'historique
+const REWIND_INTERVAL = 2;
+const REWIND_SNAPSHOTS = 300;
+
+// Mappings clavier et manette dans le localStorage : globaux, ou par ROM avec le nom en suffixe
+const INPUT_MAPPINGS = {
+    keyboard: {
+        storageKey: 'ferris8.keymap',
+        select: 'keymap-preset',
+        defaultPreset: 'qwerty',
+        setPreset: (emulator, preset) => emulator.set_key_map_preset(preset),
+        load: (emulator, json) => emulator.load_key_map(json),
+        export: (emulator) => emulator.export_key_map()
+    },
+    gamepad: {
+        storageKey: 'ferris8.gamepad',
+        select: 'gamepad-preset',
+        defaultPreset: 'octo',
+        setPreset: (emulator, preset) => emulator.set_gamepad_preset(preset),
+        load: (emulator, json) => emulator.load_gamepad_map(json),
+        export: (emulator) => emulator.export_gamepad_map()
+    }
 };
 
+// Trace d'exécution : les 100 000 dernières instructions, 8 affichées
+const TRACE_SIZE = 100000;
+const TRACE_LINES = 8;
+
+// Couleurs des index de plans : éteint, plan 1, plan 2, les deux
+const PALETTE = [
+    [0x00, 0x00, 0x00],
+    [0xFF, 0xFF, 0xFF],
+    [0xAA, 0xAA, 0xAA],
+    [0x55, 0x55, 0x55]
+];
+
 class Ferris8App {
     constructor() {
         this.emulator = null;
@@ -91,6 +115,16 @@ class Ferris8App {
         this.cyclesPerSecond = 500;
         this.lastCycleCount = 0;
         this.actualSpeed = 0;
+        this.lastFrameTime = 0;
+        this.frameAccumulator = 0;
+        this.rewinding = false;
+        this.palette = PALETTE;
+        this.tracing = false;
+        this.currentRomName = null;
+
+        // Sauvegardes rapides (slot -> Uint8Array)
+        this.saveSlots = new Map();
+        this.lastSavedState = null;
 
         this.canvas = document.getElementById('display');
         this.ctx = this.canvas.getContext('2d');
@@ -134,6 +168,9 @@ class Ferris8App {
 
             // Créer l'émulateur
             this.emulator = new Emulator();
+            this.emulator.set_audio_sample_rate(audioSystem.sampleRate);
+            this.emulator.set_rewind(REWIND_SNAPSHOTS, REWIND_INTERVAL);
+            this.restoreInputMappings(null);
             console.log('✅ Émulateur créé');
 
             // Vérifier que l'émulateur fonctionne
@@ -179,9 +216,40 @@ class Ferris8App {
         document.getElementById('btn-stop').addEventListener('click', () => this.stop());
         document.getElementById('btn-reset').addEventListener('click', () => this.reset());
 
+        // Sauvegardes rapides
+        document.querySelectorAll('.save-slot').forEach(button => {
+            button.addEventListener('click', () => this.saveState(button.dataset.slot));
+        });
+        document.querySelectorAll('.load-slot').forEach(button => {
+            button.addEventListener('click', () => this.loadState(button.dataset.slot));
+        });
+        document.getElementById('btn-export-state').addEventListener('click', () => this.exportState());
+
+        // Films : enregistrement et relecture des touches
+        document.getElementById('btn-record-movie').addEventListener('click', () => this.startRecording());
+        document.getElementById('btn-stop-movie').addEventListener('click', () => this.stopMovie());
+        document.getElementById('movie-input').addEventListener('change', (e) => {
+            this.playMovie(e.target.files[0]);
+            e.target.value = '';
+        });
+
+        // Retour arrière (maintenir le bouton ou Backspace)
+        const rewindButton = document.getElementById('btn-rewind');
+        rewindButton.addEventListener('mousedown', () => this.setRewinding(true));
+        rewindButton.addEventListener('mouseup', () => this.setRewinding(false));
+        rewindButton.addEventListener('mouseleave', () => this.setRewinding(false));
+        rewindButton.addEventListener('touchstart', (e) => {
+            e.preventDefault();
+            this.setRewinding(true);
+        });
+        rewindButton.addEventListener('touchend', (e) => {
+            e.preventDefault();
+            this.setRewinding(false);
+        });
+
         // File upload zone avec drag & drop
         this.setupFileUpload();
-        
+
         // Boutons ROMs de test
         document.getElementById('btn-rom-simple').addEventListener('click', () => this.loadTestROM('simple'));
         document.getElementById('btn-rom-blink').addEventListener('click', () => this.loadTestROM('blink'));
@@ -191,6 +259,7 @@ class Ferris8App {
         // Custom ROM dropdown
         this.setupCustomDropdown();
         document.getElementById('btn-load-selected-rom').addEventListener('click', () => this.loadSelectedROM());
+        document.getElementById('btn-assemble').addEventListener('click', () => this.assembleSource());
 
         // Paramètres
         document.getElementById('speed-slider').addEventListener('input', (e) => {
@@ -198,10 +267,73 @@ class Ferris8App {
             document.getElementById('speed-value').textContent = this.cyclesPerSecond;
         });
 
+        document.getElementById('platform-select').addEventListener('change', (e) => {
+            if (this.emulator && this.emulator.set_platform(e.target.value)) {
+                console.log('🖥️ Plateforme:', e.target.value);
+                const presets = { chip8: 'vip', schip: 'schip', 'xo-chip': 'xo-chip' };
+                document.getElementById('quirks-preset').value = presets[e.target.value];
+                document.getElementById('stack-depth').value = this.emulator.get_stack_depth();
+            }
+        });
+
+        document.getElementById('quirks-preset').addEventListener('change', (e) => {
+            if (this.emulator && this.emulator.set_quirks_preset(e.target.value)) {
+                console.log('🧩 Quirks:', e.target.value);
+            }
+        });
+
+        document.getElementById('policy-select').addEventListener('change', (e) => {
+            if (this.emulator && this.emulator.set_execution_policy(e.target.value)) {
+                console.log('🛡️ Politique d\'exécution:', e.target.value);
+                // La politique choisit la protection des fonts par défaut
+                document.getElementById('font-protection').checked = e.target.value !== 'accurate';
+            }
+        });
+
+        for (const [kind, mapping] of Object.entries(INPUT_MAPPINGS)) {
+            document.getElementById(mapping.select).addEventListener('change', (e) => {
+                if (this.emulator && mapping.setPreset(this.emulator, e.target.value)) {
+                    console.log(`🎮 Mapping ${kind}:`, e.target.value);
+                    this.storeInputMapping(kind, e.target.value);
+                }
+            });
+            document.getElementById(`${mapping.select}-input`).addEventListener('change', (e) => this.importInputMapping(kind, e.target.files[0]));
+            document.getElementById(`btn-export-${mapping.select}`).addEventListener('click', () => this.exportInputMapping(kind));
+        }
+        document.getElementById('keymap-per-rom').addEventListener('change', (e) => this.setInputMappingsPerROM(e.target.checked));
+        document.getElementById('gamepad-dead-zone').addEventListener('change', (e) => {
+            if (!this.emulator) return;
+            this.emulator.set_gamepad_dead_zone(parseFloat(e.target.value));
+            this.storeInputMapping('gamepad', document.getElementById('gamepad-preset').value);
+        });
+
+        document.getElementById('stack-depth').addEventListener('change', (e) => {
+            if (this.emulator && !this.emulator.set_stack_depth(Number(e.target.value))) {
+                console.warn('⚠️ Profondeur de pile refusée: trop d\'appels en cours');
+                e.target.value = this.emulator.get_stack_depth();
+            }
+        });
+
+        document.getElementById('rom-database').addEventListener('change', (e) => {
+            if (this.emulator) {
+                this.emulator.set_rom_database_enabled(e.target.checked);
+            }
+        });
+        document.getElementById('rom-database-input').addEventListener('change', (e) => this.importRomDatabase(e.target.files[0]));
+
+        document.getElementById('font-protection').addEventListener('change', (e) => {
+            if (this.emulator) {
+                this.emulator.set_font_protection(e.target.checked);
+            }
+        });
+
         document.getElementById('sound-enabled').addEventListener('change', (e) => {
             const enabled = e.target.checked;
             console.log('🔊 Son', enabled ? 'activé' : 'désactivé');
             audioSystem.setEnabled(enabled);
+            if (this.emulator) {
+                this.emulator.set_sound_enabled(enabled);
+            }
         });
 
         // Clavier
@@ -235,6 +367,27 @@ class Ferris8App {
 
         // Debug
         document.getElementById('btn-memory-dump').addEventListener('click', () => this.memoryDump());
+        document.getElementById('disasm-syntax').addEventListener('change', (e) => {
+            if (this.emulator && this.emulator.set_disassembly_syntax(e.target.value)) {
+                this.updateDisassembly();
+            }
+        });
+        document.getElementById('trace-enabled').addEventListener('change', (e) => this.setTracing(e.target.checked));
+        document.getElementById('btn-export-trace').addEventListener('click', () => this.exportTrace());
+        document.getElementById('trace-reference-input').addEventListener('change', (e) => this.diffTrace(e.target.files[0]));
+        document.getElementById('symbols-input').addEventListener('change', (e) => this.loadSymbols(e.target.files[0]));
+        document.getElementById('btn-step').addEventListener('click', () => this.debugStep(e => e.step_instruction()));
+        document.getElementById('btn-step-over').addEventListener('click', () => this.debugStep(e => e.step_over()));
+        document.getElementById('btn-step-out').addEventListener('click', () => this.debugStep(e => e.step_out()));
+        document.getElementById('btn-continue').addEventListener('click', () => this.start());
+        document.getElementById('btn-add-breakpoint').addEventListener('click', () => this.addBreakpoint());
+        document.getElementById('btn-add-watchpoint').addEventListener('click', () => this.addWatchpoint());
+        document.getElementById('btn-clear-breakpoints').addEventListener('click', () => {
+            if (!this.emulator) return;
+            this.emulator.clear_breakpoints();
+            this.emulator.clear_watchpoints();
+            document.getElementById('debugger-info').textContent = 'Breakpoints effacés';
+        });
 
         // Gestion des erreurs globales
         window.addEventListener('error', (e) => {
@@ -278,7 +431,7 @@ class Ferris8App {
         uploadZone.addEventListener('drop', (e) => {
             e.preventDefault();
             uploadZone.classList.remove('dragover');
-            
+
             const files = e.dataTransfer.files;
             if (files.length > 0) {
                 this.handleFileUpload(files[0]);
@@ -290,59 +443,59 @@ class Ferris8App {
 
     async handleFileUpload(file) {
         const uploadZone = document.getElementById('file-upload-zone');
-        
+
         try {
             console.log(`📁 Upload de ${file.name} (${file.size} bytes)...`);
-            
+
             // Visual feedback
             uploadZone.classList.add('loading');
-            
+
             // Validate file
             if (!file.name.match(/\.(ch8|c8)$/i)) {
                 throw new Error('Format non supporté. Utilisez .ch8 ou .c8');
             }
-            
-            if (file.size > 3584) {
-                throw new Error(`ROM trop grosse: ${file.size} bytes > 3584 bytes max`);
-            }
-            
+
             if (file.size === 0) {
                 throw new Error('ROM vide');
             }
-            
+
             // Reset before loading
             this.reset();
-            
+
             // Load file
             const arrayBuffer = await file.arrayBuffer();
             const romData = new Uint8Array(arrayBuffer);
-            
-            // Load ROM
-            this.emulator.load_rom(romData);
-            
+
+            // Load ROM (la base de ROMs peut choisir une plateforme plus grande)
+            if (!this.emulator.load_rom(romData)) {
+                throw new Error(`ROM trop grosse: ${file.size} bytes > ${this.emulator.max_rom_size()} bytes max (essayez XO-CHIP)`);
+            }
+
             // Success feedback
             uploadZone.classList.remove('loading');
             uploadZone.classList.add('success');
             setTimeout(() => {
                 uploadZone.classList.remove('success');
             }, 2000);
-            
+
             console.log(`✅ ${file.name} chargée avec succès`);
-            
+
             // Update UI
             this.displayROMInfo(file.name, romData);
+            this.applyRomSettings();
+            this.restoreInputMappings(file.name);
             this.updateStatus(`🎮 ROM: ${file.name}`);
-            
+
         } catch (error) {
             console.error('❌ Erreur upload ROM:', error);
-            
+
             // Error feedback
             uploadZone.classList.remove('loading');
             uploadZone.classList.add('error');
             setTimeout(() => {
                 uploadZone.classList.remove('error');
             }, 3000);
-            
+
             this.handleError('Erreur upload ROM', error);
         }
     }
@@ -362,9 +515,12 @@ class Ferris8App {
 
         try {
             console.log('▶️ Démarrage de l\'émulateur');
+            audioSystem.resume();
             this.emulator.start();
             this.running = true;
             this.errorCount = 0;
+            this.lastFrameTime = 0;
+            this.frameAccumulator = FRAME_DURATION;
             this.runLoop();
             this.updateStatus('🟢 En cours');
 
@@ -439,18 +595,36 @@ class Ferris8App {
         try {
             const now = performance.now();
 
-            // Calculer le nombre de cycles à exécuter (limité pour éviter les blocages)
-            const targetCycles = Math.floor(this.cyclesPerSecond / 60);
-            const maxCycles = Math.min(targetCycles, 50);
+            // Nombre de frames de 1/60 s écoulées (limité pour éviter les rattrapages massifs)
+            this.frameAccumulator += now - (this.lastFrameTime || now);
+            this.lastFrameTime = now;
+            const framesToRun = Math.min(Math.floor(this.frameAccumulator / FRAME_DURATION), 4);
+            this.frameAccumulator -= framesToRun * FRAME_DURATION;
+            if (framesToRun === 4) {
+                this.frameAccumulator = 0;
+            }
+
+            // Instructions par frame, les timers tournent toujours à 60 Hz
+            const cyclesPerFrame = Math.max(1, Math.round(this.cyclesPerSecond / 60));
+
+            // Retour arrière : on remonte le temps au lieu d'exécuter les frames
+            if (this.rewinding && framesToRun > 0) {
+                this.emulator.rewind(framesToRun);
+            }
+
+            if (framesToRun > 0) {
+                this.pollGamepads();
+            }
 
-            // Exécuter les cycles avec protection
-            for (let i = 0; i < maxCycles; i++) {
-                if (!this.running) break;
+            // Exécuter les frames avec protection
+            for (let i = 0; i < framesToRun; i++) {
+                if (!this.running || this.rewinding) break;
 
                 try {
-                    this.emulator.cycle();
+                    this.emulator.run_frame(cyclesPerFrame);
+                    if (this.checkHalt()) return;
                 } catch (error) {
-                    console.error('❌ Erreur dans cycle:', error);
+                    console.error('❌ Erreur dans run_frame:', error);
                     this.errorCount++;
 
                     if (this.errorCount > 5) {
@@ -461,6 +635,9 @@ class Ferris8App {
                 }
             }
 
+            // Envoyer le son synthétisé pendant ces frames
+            audioSystem.pump(this.emulator);
+
             // Mise à jour de l'affichage
             this.updateDisplay();
             this.updateDebugInfo();
@@ -473,8 +650,7 @@ class Ferris8App {
                 this.lastFpsUpdate = now;
 
                 // Calculer la vitesse réelle
-                const debugInfo = this.emulator.get_debug_info();
-                const currentCycles = this.extractCycleCount(debugInfo);
+                const currentCycles = Number(this.emulator.get_cycle_count());
                 this.actualSpeed = currentCycles - this.lastCycleCount;
                 this.lastCycleCount = currentCycles;
 
@@ -490,53 +666,49 @@ class Ferris8App {
         }
     }
 
-    extractCycleCount(debugInfo) {
-        const match = debugInfo.match(/Cycles: (\d+)/);
-        return match ? parseInt(match[1]) : 0;
-    }
-
     // ========== AFFICHAGE ==========
 
     updateDisplay() {
         if (!this.emulator) return;
 
         try {
-            // Récupérer le buffer d'affichage depuis Rust
-            const buffer = this.emulator.get_display_buffer();
+            // Index de couleur par pixel depuis Rust (64x32 ou 128x64, 4 couleurs en XO-CHIP)
+            const buffer = this.emulator.get_plane_buffer();
+            const width = this.emulator.get_display_width();
+            const height = this.emulator.get_display_height();
 
-            if (!buffer || buffer.length !== 2048) {
+            if (!buffer || buffer.length !== width * height) {
                 console.warn('⚠️ Buffer d\'affichage invalide:', buffer?.length);
                 return;
             }
 
-            // Créer ImageData pour les vrais pixels (64x32)
-            const imageData = this.ctx.createImageData(64, 32);
+            // Créer ImageData pour les vrais pixels
+            const imageData = this.ctx.createImageData(width, height);
 
-            // Convertir le buffer monochrome en RGBA
+            // Convertir les index de plans en RGBA via la palette
             for (let i = 0; i < buffer.length; i++) {
-                const pixelValue = buffer[i];
+                const [r, g, b] = this.palette[buffer[i] & 3];
                 const baseIndex = i * 4;
 
-                // Blanc si pixel allumé, noir sinon
-                imageData.data[baseIndex] = pixelValue; // Rouge
-                imageData.data[baseIndex + 1] = pixelValue; // Vert  
-                imageData.data[baseIndex + 2] = pixelValue; // Bleu
+                imageData.data[baseIndex] = r; // Rouge
+                imageData.data[baseIndex + 1] = g; // Vert
+                imageData.data[baseIndex + 2] = b; // Bleu
                 imageData.data[baseIndex + 3] = 255; // Alpha (toujours opaque)
             }
 
             // Créer un canvas temporaire pour le rendu
             const tempCanvas = document.createElement('canvas');
-            tempCanvas.width = 64;
-            tempCanvas.height = 32;
+            tempCanvas.width = width;
+            tempCanvas.height = height;
             const tempCtx = tempCanvas.getContext('2d');
             tempCtx.putImageData(imageData, 0, 0);
 
             // Effacer le canvas principal
             this.clearCanvas();
 
-            // Redimensionner de 64x32 à 640x320 (x10) avec rendu net
+            // Redimensionner vers 640x320 (x10 ou x5) avec rendu net
             this.ctx.imageSmoothingEnabled = false;
-            this.ctx.drawImage(tempCanvas, 0, 0, 64, 32, 0, 0, 640, 320);
+            this.ctx.drawImage(tempCanvas, 0, 0, width, height, 0, 0, 640, 320);
 
         } catch (error) {
             console.error('❌ Erreur d\'affichage:', error);
@@ -552,6 +724,15 @@ class Ferris8App {
     // ========== GESTION DU CLAVIER ==========
 
     handleKeyDown(event) {
+        // Ne pas intercepter la saisie dans les champs du debugger
+        if (event.target.tagName === 'INPUT' || event.target.tagName === 'TEXTAREA') return;
+
+        if (event.code === 'Backspace') {
+            event.preventDefault();
+            this.setRewinding(true);
+            return;
+        }
+
         const chip8Key = this.mapKeyToChip8(event.code);
         if (chip8Key !== null && !this.pressedKeys.has(chip8Key)) {
             this.pressedKeys.add(chip8Key);
@@ -566,6 +747,14 @@ class Ferris8App {
     }
 
     handleKeyUp(event) {
+        // Ne pas intercepter la saisie dans les champs du debugger
+        if (event.target.tagName === 'INPUT' || event.target.tagName === 'TEXTAREA') return;
+
+        if (event.code === 'Backspace') {
+            this.setRewinding(false);
+            return;
+        }
+
         const chip8Key = this.mapKeyToChip8(event.code);
         if (chip8Key !== null && this.pressedKeys.has(chip8Key)) {
             this.pressedKeys.delete(chip8Key);
@@ -598,13 +787,155 @@ class Ferris8App {
     }
 
     mapKeyToChip8(keyCode) {
-        const mapping = {
-            'Digit1': 0x1, 'Digit2': 0x2, 'Digit3': 0x3, 'Digit4': 0xC,
-            'KeyQ': 0x4, 'KeyW': 0x5, 'KeyE': 0x6, 'KeyR': 0xD,
-            'KeyA': 0x7, 'KeyS': 0x8, 'KeyD': 0x9, 'KeyF': 0xE,
-            'KeyZ': 0xA, 'KeyX': 0x0, 'KeyC': 0xB, 'KeyV': 0xF
-        };
-        return mapping[keyCode] || null;
+        if (!this.emulator) return null;
+        return this.emulator.map_key(keyCode) ?? null;
+    }
+
+    // ========== MAPPING CLAVIER ET MANETTE ==========
+
+    inputMappingStorageKey(kind, romName) {
+        const base = INPUT_MAPPINGS[kind].storageKey;
+        return romName ? `${base}.rom.${romName}` : base;
+    }
+
+    // Mappings de la ROM s'ils existent, sinon les mappings globaux, sinon les presets par défaut
+    restoreInputMappings(romName) {
+        this.currentRomName = romName;
+        let perROM = false;
+
+        for (const [kind, mapping] of Object.entries(INPUT_MAPPINGS)) {
+            let stored = null;
+            try {
+                const romEntry = romName ? localStorage.getItem(this.inputMappingStorageKey(kind, romName)) : null;
+                perROM ||= romEntry !== null;
+                stored = JSON.parse(romEntry ?? localStorage.getItem(mapping.storageKey));
+            } catch (error) {
+                console.warn(`⚠️ Mapping ${kind} illisible, preset par défaut utilisé:`, error);
+            }
+
+            let preset = stored?.preset ?? mapping.defaultPreset;
+            try {
+                mapping.load(this.emulator, stored?.map ?? '');
+            } catch {
+                preset = mapping.defaultPreset;
+                mapping.setPreset(this.emulator, preset);
+            }
+            document.getElementById(mapping.select).value = preset;
+        }
+
+        // Sans mapping propre à la ROM, ses contrôles connus priment
+        if (romName && !perROM && this.emulator.apply_rom_controls()) {
+            console.log('🎮 Contrôles de la ROM appliqués depuis la base');
+        }
+
+        document.getElementById('keymap-per-rom').checked = perROM;
+        document.getElementById('keymap-per-rom').disabled = !romName;
+        document.getElementById('gamepad-dead-zone').value = this.emulator.get_gamepad_dead_zone().toFixed(2);
+    }
+
+    // Enregistrer un mapping pour la ROM (si coché) ou pour toutes
+    storeInputMapping(kind, preset) {
+        const mapping = INPUT_MAPPINGS[kind];
+        const perROM = document.getElementById('keymap-per-rom').checked && this.currentRomName;
+        const entry = JSON.stringify({ preset, map: mapping.export(this.emulator) });
+        try {
+            localStorage.setItem(this.inputMappingStorageKey(kind, perROM ? this.currentRomName : null), entry);
+        } catch (error) {
+            console.warn(`⚠️ Impossible d'enregistrer le mapping ${kind}:`, error);
+        }
+    }
+
+    setInputMappingsPerROM(enabled) {
+        if (!this.emulator || !this.currentRomName) return;
+
+        for (const [kind, mapping] of Object.entries(INPUT_MAPPINGS)) {
+            if (enabled) {
+                this.storeInputMapping(kind, document.getElementById(mapping.select).value);
+            } else {
+                try {
+                    localStorage.removeItem(this.inputMappingStorageKey(kind, this.currentRomName));
+                } catch (error) {
+                    console.warn('⚠️ Impossible d\'oublier le mapping de la ROM:', error);
+                }
+            }
+        }
+        if (!enabled) {
+            this.restoreInputMappings(this.currentRomName);
+        }
+    }
+
+    // Vitesse, plateforme, quirks et couleurs retenus par la base de ROMs
+    applyRomSettings() {
+        const title = this.emulator.get_rom_title();
+        const cyclesPerFrame = this.emulator.get_rom_cycles_per_frame();
+        if (cyclesPerFrame > 0) {
+            this.cyclesPerSecond = cyclesPerFrame * 60;
+            document.getElementById('speed-slider').value = this.cyclesPerSecond;
+            document.getElementById('speed-value').textContent = this.cyclesPerSecond;
+        }
+
+        document.getElementById('platform-select').value = this.emulator.get_platform();
+        document.getElementById('stack-depth').value = this.emulator.get_stack_depth();
+        if (title) {
+            document.getElementById('quirks-preset').value = 'database';
+            console.log(`📚 ROM reconnue: ${title} (${this.emulator.get_rom_sha1()})`);
+        }
+
+        const colors = Array.from(this.emulator.get_rom_palette());
+        this.palette = PALETTE.map((fallback, index) => {
+            const color = colors[index];
+            return color === undefined ? fallback : [(color >> 16) & 0xFF, (color >> 8) & 0xFF, color & 0xFF];
+        });
+    }
+
+    async importRomDatabase(file) {
+        if (!file || !this.emulator) return;
+
+        try {
+            const count = this.emulator.load_rom_database(await file.text());
+            console.log(`📚 ${count} ROMs importées depuis ${file.name} (${this.emulator.rom_database_size()} connues)`);
+        } catch (error) {
+            this.showError(`Base de ROMs invalide: ${error}`);
+        }
+    }
+
+    async importInputMapping(kind, file) {
+        if (!file || !this.emulator) return;
+
+        const mapping = INPUT_MAPPINGS[kind];
+        try {
+            mapping.load(this.emulator, await file.text());
+            document.getElementById(mapping.select).value = 'custom';
+            this.storeInputMapping(kind, 'custom');
+            console.log(`🎮 Mapping ${kind} importé depuis ${file.name}`);
+        } catch (error) {
+            this.showError(`Mapping ${kind} invalide: ${error}`);
+        }
+    }
+
+    exportInputMapping(kind) {
+        if (!this.emulator) return;
+
+        const blob = new Blob([INPUT_MAPPINGS[kind].export(this.emulator)], { type: 'application/json' });
+        const link = document.createElement('a');
+        link.href = URL.createObjectURL(blob);
+        link.download = `ferris8-${kind}.json`;
+        link.click();
+        URL.revokeObjectURL(link.href);
+    }
+
+    // Relever les manettes (si le module a été compilé avec la feature gamepad)
+    pollGamepads() {
+        if (!this.emulator.poll_gamepads) return;
+
+        const previous = this.emulator.get_gamepad_keys();
+        this.emulator.poll_gamepads();
+        const changed = previous ^ this.emulator.get_gamepad_keys();
+        for (let key = 0; key < 16; key++) {
+            if (changed & (1 << key)) {
+                this.updateVirtualKeyboard(key, (this.emulator.get_gamepad_keys() >> key) & 1);
+            }
+        }
     }
 
     hexToChip8(hex) {
@@ -659,6 +990,8 @@ class Ferris8App {
             console.log(`✅ ROM ${romName} chargée: ${testROM.length} bytes`);
 
             this.displayROMInfo(romName, testROM);
+            this.applyRomSettings();
+            this.restoreInputMappings(romName);
             this.updateStatus(`🎮 ${romName}`);
 
         } catch (error) {
@@ -686,7 +1019,7 @@ class Ferris8App {
         try {
             console.log('📂 Chargement de la liste des ROMs...');
             const response = await fetch('./roms/index.json');
-            
+
             if (!response.ok) {
                 console.warn('⚠️ Impossible de charger index.json, mode ROM externe désactivé');
                 return;
@@ -694,10 +1027,10 @@ class Ferris8App {
 
             const data = await response.json();
             this.availableROMs = data.roms || [];
-            
+
             console.log(`✅ ${this.availableROMs.length} ROMs trouvées`);
             this.populateROMSelector();
-            
+
         } catch (error) {
             console.warn('⚠️ Erreur chargement ROMs:', error.message);
             this.availableROMs = [];
@@ -708,17 +1041,17 @@ class Ferris8App {
         const header = document.getElementById('dropdown-header');
         const menu = document.getElementById('dropdown-menu');
         const searchInput = document.getElementById('rom-search');
-        
+
         console.log('🔧 Setup dropdown:', { header, menu, searchInput });
-        
+
         if (!header || !menu) {
             console.error('❌ Éléments dropdown introuvables!');
             return;
         }
-        
+
         header.addEventListener('click', () => {
             console.log('🖱️ Click sur dropdown header');
-            
+
             if (menu.classList.contains('open')) {
                 // Fermer
                 header.classList.remove('active');
@@ -728,7 +1061,7 @@ class Ferris8App {
                 this.positionDropdown(header, menu);
                 header.classList.add('active');
                 menu.classList.add('open');
-                
+
                 // Focus search input when opening
                 if (searchInput) {
                     setTimeout(() => searchInput.focus(), 100);
@@ -771,13 +1104,13 @@ class Ferris8App {
 
     populateROMSelector() {
         const content = document.getElementById('dropdown-content');
-        
+
         // Clear existing options
         content.innerHTML = '';
 
         // Group ROMs by category
         const categories = [...new Set(this.availableROMs.map(rom => rom.category))];
-        
+
         categories.forEach((category, categoryIndex) => {
             // Add category header if we have multiple categories
             if (categories.length > 1) {
@@ -787,7 +1120,7 @@ class Ferris8App {
                 categoryHeader.dataset.category = category;
                 content.appendChild(categoryHeader);
             }
-            
+
             // Add ROMs for this category
             this.availableROMs
                 .filter(rom => rom.category === category)
@@ -797,17 +1130,17 @@ class Ferris8App {
                     option.dataset.romData = JSON.stringify(rom);
                     option.dataset.category = rom.category;
                     option.dataset.searchText = `${rom.name} ${rom.description} ${rom.author}`.toLowerCase();
-                    
+
                     option.innerHTML = `
                         <div class="option-name">${rom.name}</div>
                         <div class="option-meta">${rom.author} • ${rom.year} • ${rom.category}</div>
                         <div class="option-description">${rom.description}</div>
                     `;
-                    
+
                     option.addEventListener('click', () => this.onROMSelected(rom, option));
                     content.appendChild(option);
                 });
-            
+
             // Add separator between categories (except for the last one)
             if (categories.length > 1 && categoryIndex < categories.length - 1) {
                 const separator = document.createElement('div');
@@ -829,31 +1162,31 @@ class Ferris8App {
 
         // Remove previous selection
         content.querySelectorAll('.dropdown-option').forEach(opt => opt.classList.remove('selected'));
-        
+
         // Mark as selected
         optionElement.classList.add('selected');
-        
+
         // Update header text
         document.querySelector('.dropdown-text').textContent = romData.name;
-        
+
         // Close dropdown
         header.classList.remove('active');
         menu.classList.remove('open');
-        
+
         // Store selected ROM
         this.selectedROM = romData;
-        
+
         // Enable load button
         loadButton.disabled = false;
-        
+
         // Show ROM info
         document.getElementById('rom-title').textContent = romData.name;
         document.getElementById('rom-description').textContent = romData.description;
         document.getElementById('rom-meta').textContent = 
             `${romData.author} • ${romData.year} • ${romData.category}`;
-        
+
         romInfo.classList.remove('hidden');
-        
+
         console.log('🎯 ROM sélectionnée:', romData.name);
     }
 
@@ -862,7 +1195,7 @@ class Ferris8App {
         const options = content.querySelectorAll('.dropdown-option');
         const categories = content.querySelectorAll('.dropdown-category');
         const separators = content.querySelectorAll('.dropdown-separator');
-        
+
         const term = searchTerm.toLowerCase().trim();
         let visibleCount = 0;
         let visibleCategories = new Set();
@@ -871,9 +1204,9 @@ class Ferris8App {
         options.forEach(option => {
             const searchText = option.dataset.searchText;
             const isVisible = !term || searchText.includes(term);
-            
+
             option.classList.toggle('hidden', !isVisible);
-            
+
             if (isVisible) {
                 visibleCount++;
                 visibleCategories.add(option.dataset.category);
@@ -906,6 +1239,22 @@ class Ferris8App {
         }
     }
 
+    assembleSource() {
+        const source = document.getElementById('source-editor').value;
+
+        try {
+            this.reset();
+            this.emulator.load_source(source, 'editor.8o');
+
+            console.log('✅ Source assemblé');
+            this.updateStatus('✍️ Programme assemblé');
+            this.start();
+        } catch (error) {
+            // Les erreurs d'assemblage arrivent sous la forme "ligne N: message"
+            this.showError(`Assemblage: ${error}`);
+        }
+    }
+
     async loadSelectedROM() {
         if (!this.selectedROM) {
             this.showError('Aucune ROM sélectionnée');
@@ -914,38 +1263,38 @@ class Ferris8App {
 
         try {
             console.log(`📁 Chargement de ${this.selectedROM.name}...`);
-            
+
             // Reset before loading
             this.reset();
-            
+
             // Fetch ROM file
             const response = await fetch(`./roms/${this.selectedROM.file}`);
-            
+
             if (!response.ok) {
                 throw new Error(`ROM non trouvée: ${this.selectedROM.file}`);
             }
-            
+
             const arrayBuffer = await response.arrayBuffer();
             const romData = new Uint8Array(arrayBuffer);
-            
+
             // Validate ROM size
             if (romData.length === 0) {
                 throw new Error('ROM vide');
             }
-            
-            if (romData.length > 3584) {
-                throw new Error(`ROM trop grosse: ${romData.length} bytes > 3584 bytes max`);
+
+            // Load ROM (la base de ROMs peut choisir une plateforme plus grande)
+            if (!this.emulator.load_rom(romData)) {
+                throw new Error(`ROM trop grosse: ${romData.length} bytes > ${this.emulator.max_rom_size()} bytes max (essayez XO-CHIP)`);
             }
-            
-            // Load ROM
-            this.emulator.load_rom(romData);
-            
+
             console.log(`✅ ${this.selectedROM.name} chargée: ${romData.length} bytes`);
-            
+
             // Update UI
             this.displayROMInfo(this.selectedROM.name, romData);
+            this.applyRomSettings();
+            this.restoreInputMappings(this.selectedROM.name);
             this.updateStatus(`🎮 ${this.selectedROM.name}`);
-            
+
         } catch (error) {
             console.error('❌ Erreur chargement ROM:', error);
             this.handleError('Erreur chargement ROM', error);
@@ -1016,7 +1365,7 @@ class Ferris8App {
         return new Uint8Array([
             // Effacer l'écran d'abord
             0x00, 0xE0,   // CLS [0x200]
-            
+
             // Afficher 'B' (0xB)
             0x60, 0x10, // V0 = 16 (pos X pour B) [0x202]
             0x61, 0x0C, // V1 = 12 (pos Y) [0x204]
@@ -1035,6 +1384,132 @@ class Ferris8App {
         ]);
     }
 
+    // ========== SAUVEGARDES ==========
+
+    saveState(slot) {
+        if (!this.emulator) {
+            this.showError('Émulateur non initialisé');
+            return;
+        }
+
+        try {
+            const state = this.emulator.save_state();
+            this.saveSlots.set(slot, state);
+            this.lastSavedState = state;
+            console.log(`💾 État sauvegardé dans le slot ${slot}: ${state.length} bytes`);
+
+            document.querySelector(`.load-slot[data-slot="${slot}"]`).disabled = false;
+            document.getElementById('btn-export-state').disabled = false;
+        } catch (error) {
+            this.handleError('Erreur de sauvegarde', error);
+        }
+    }
+
+    loadState(slot) {
+        const state = this.saveSlots.get(slot);
+        if (!this.emulator || !state) return;
+
+        try {
+            this.emulator.load_state(state);
+            console.log(`📂 État restauré depuis le slot ${slot}`);
+            this.updateDisplay();
+            this.updateDebugInfo();
+        } catch (error) {
+            this.handleError('Erreur de restauration', error);
+        }
+    }
+
+    setRewinding(rewinding) {
+        if (!this.emulator || this.rewinding === rewinding) return;
+        this.rewinding = rewinding;
+
+        // À l'arrêt, chaque appui remonte d'une seconde
+        if (rewinding && !this.running) {
+            if (this.emulator.rewind(60)) {
+                this.updateDisplay();
+                this.updateDebugInfo();
+            }
+        }
+
+        const available = (this.emulator.rewind_available_frames() / 60).toFixed(1);
+        document.getElementById('btn-rewind').title = `${available} s d'historique`;
+    }
+
+    // Télécharger le dernier état sauvegardé (pour joindre à un rapport de bug)
+    exportState() {
+        if (!this.lastSavedState) return;
+
+        const blob = new Blob([this.lastSavedState], { type: 'application/octet-stream' });
+        const link = document.createElement('a');
+        link.href = URL.createObjectURL(blob);
+        link.download = 'ferris8-state.f8st';
+        link.click();
+        URL.revokeObjectURL(link.href);
+    }
+
+    // ========== FILMS ==========
+
+    startRecording() {
+        if (!this.emulator) return;
+
+        try {
+            this.emulator.start_recording();
+            this.updateDisplay();
+            this.updateMovieStatus();
+            if (!this.running) this.start();
+        } catch (error) {
+            this.showError(`Enregistrement impossible: ${error}`);
+        }
+    }
+
+    // Arrêter l'enregistrement (le film est téléchargé) ou la relecture
+    stopMovie() {
+        if (!this.emulator) return;
+
+        if (this.emulator.is_recording()) {
+            const movie = this.emulator.stop_recording();
+            const blob = new Blob([movie], { type: 'application/octet-stream' });
+            const link = document.createElement('a');
+            link.href = URL.createObjectURL(blob);
+            link.download = `${this.currentRomName ?? 'ferris8'}.f8mv`;
+            link.click();
+            URL.revokeObjectURL(link.href);
+        } else {
+            this.emulator.stop_movie();
+        }
+        this.updateMovieStatus();
+    }
+
+    // Le film doit avoir été enregistré avec la ROM chargée
+    async playMovie(file) {
+        if (!file || !this.emulator) return;
+
+        try {
+            this.emulator.play_movie(new Uint8Array(await file.arrayBuffer()));
+            console.log(`🎬 Lecture du film ${file.name}`);
+            this.updateDisplay();
+            this.updateMovieStatus();
+            if (!this.running) this.start();
+        } catch (error) {
+            this.showError(`Film invalide: ${error}`);
+        }
+    }
+
+    updateMovieStatus() {
+        const recording = this.emulator.is_recording();
+        const playing = this.emulator.is_playing_movie();
+        const seconds = (this.emulator.get_movie_frame() / 60).toFixed(1);
+        let status = 'Aucun film';
+        if (recording) {
+            status = `⏺️ Enregistrement: ${seconds} s`;
+        } else if (playing) {
+            const length = (this.emulator.get_movie_length() / 60).toFixed(1);
+            status = `▶️ Lecture: ${seconds} / ${length} s`;
+        }
+        document.getElementById('movie-status').textContent = status;
+        document.getElementById('btn-stop-movie').disabled = !recording && !playing;
+    }
+
     // ========== DEBUG ET MONITORING ==========
 
     updateDebugInfo() {
@@ -1043,9 +1518,13 @@ class Ferris8App {
         try {
             const debugInfo = this.emulator.get_debug_info();
             document.getElementById('registers-info').textContent = debugInfo;
+            document.getElementById('call-stack').textContent = this.emulator.describe_call_stack() || '(vide)';
+            this.updateDisassembly();
+            this.updateTrace();
+            this.updateMovieStatus();
 
             // Vérifier l'état de santé
-            const isHealthy = debugInfo.includes('Err: 0');
+            const isHealthy = this.emulator.get_error_count() === 0;
             if (!isHealthy && this.running) {
                 console.warn('⚠️ CPU en état dégradé:', debugInfo);
             }
@@ -1055,6 +1534,68 @@ class Ferris8App {
         }
     }
 
+    // Quelques instructions autour du PC
+    updateDisassembly() {
+        const start = Math.max(0, this.emulator.get_pc() - DISASM_LINES_BEFORE_PC * 2);
+        document.getElementById('disasm-info').textContent = this.emulator.disassemble(start, DISASM_LINES);
+    }
+
+    // Table de symboles (labels, lignes source) pour la ROM chargée
+    async loadSymbols(file) {
+        if (!file || !this.emulator) return;
+
+        try {
+            this.emulator.load_symbols(await file.text());
+            console.log(`✅ Symboles chargés: ${file.name}`);
+            this.updateDebugInfo();
+        } catch (error) {
+            this.showError(`Symboles invalides: ${error}`);
+        }
+    }
+
+    setTracing(enabled) {
+        if (!this.emulator) return;
+
+        this.emulator.set_trace(enabled ? TRACE_SIZE : 0);
+        this.tracing = enabled;
+        document.getElementById('trace-info').textContent = enabled ? 'Trace vide' : 'Trace désactivée';
+    }
+
+    updateTrace() {
+        if (!this.tracing) return;
+
+        const lastLines = this.emulator.export_trace_text(TRACE_LINES);
+        document.getElementById('trace-info').textContent = lastLines || 'Trace vide';
+    }
+
+    exportTrace() {
+        if (!this.emulator || !this.tracing) {
+            this.showError('Activez la trace avant de l\'exporter');
+            return;
+        }
+
+        const blob = new Blob([this.emulator.export_trace_text(0)], { type: 'text/plain' });
+        const link = document.createElement('a');
+        link.href = URL.createObjectURL(blob);
+        link.download = 'ferris8-trace.txt';
+        link.click();
+        URL.revokeObjectURL(link.href);
+    }
+
+    // Comparer avec la trace d'un émulateur de référence (texte ou binaire .f8tr)
+    async diffTrace(file) {
+        if (!file || !this.emulator) return;
+
+        try {
+            const reference = new Uint8Array(await file.arrayBuffer());
+            const divergence = this.emulator.diff_trace(reference);
+            document.getElementById('trace-info').textContent =
+                divergence ?? `✅ Aucune divergence avec ${file.name}`;
+        } catch (error) {
+            this.showError(`Trace de référence invalide: ${error}`);
+        }
+    }
+
     memoryDump() {
         if (!this.emulator) {
             this.showError('Émulateur non initialisé');
@@ -1063,23 +1604,106 @@ class Ferris8App {
 
         try {
             console.log('🗃️ Memory dump demandé');
-            
+
             // Dump de la zone des programmes (0x200-0x2FF)
             const programDump = this.emulator.memory_dump(0x200, 256);
-            
+
             // Dump des fonts (0x50-0x9F) 
             const fontDump = this.emulator.memory_dump(0x50, 80);
-            
+
             const fullDump = `=== MEMORY DUMP ===\n\n--- FONTS (0x50-0x9F) ---\n${fontDump}\n\n--- PROGRAM AREA (0x200-0x2FF) ---\n${programDump}`;
-            
+
             console.log(fullDump);
             document.getElementById('memory-info').textContent = fullDump;
-            
+
         } catch (error) {
             this.handleError('Erreur memory dump', error);
         }
     }
 
+    // ========== DEBUGGER ==========
+
+    // Un breakpoint, un watchpoint ou la ROM elle-même a arrêté l'émulateur pendant la frame
+    checkHalt() {
+        const reason = this.emulator.get_halt_reason();
+        if (reason === undefined) return false;
+
+        this.stop();
+        if (reason === HaltReason.Breakpoint) {
+            const event = this.emulator.last_break();
+            const kinds = [BreakKind.Breakpoint, BreakKind.WatchRead, BreakKind.WatchWrite];
+            if (event && kinds.includes(event.kind)) this.showBreak(event);
+            this.updateStatus('⏸️ Arrêt debugger');
+        } else {
+            const errors = this.emulator.describe_errors() || this.emulator.get_halt_message();
+            const callStack = this.emulator.describe_call_stack();
+            document.getElementById('debugger-info').textContent =
+                callStack ? `${errors}\nPile d'appels:\n${callStack}` : errors;
+            this.updateStatus(`⏹️ ${this.emulator.get_halt_message()}`);
+        }
+        return true;
+    }
+
+    debugStep(action) {
+        if (!this.emulator) return;
+        if (this.running) this.stop();
+
+        try {
+            const event = action(this.emulator);
+            this.showBreak(event);
+        } catch (error) {
+            this.handleError('Erreur debugger', error);
+        }
+    }
+
+    showBreak(event) {
+        document.getElementById('debugger-info').textContent = event.describe();
+        this.updateDisplay();
+        this.updateDebugInfo();
+    }
+
+    parseAddress(id) {
+        const address = parseInt(document.getElementById(id).value.trim(), 16);
+        return Number.isNaN(address) || address < 0 || address > 0xFFFF ? null : address;
+    }
+
+    addBreakpoint() {
+        const address = this.parseAddress('breakpoint-address');
+        if (!this.emulator || address === null) {
+            this.showError('Adresse de breakpoint invalide');
+            return;
+        }
+
+        // Condition optionnelle "REGISTRE OP VALEUR", ex: "V3 >= 10"
+        const condition = document.getElementById('breakpoint-condition').value.trim();
+        const info = document.getElementById('debugger-info');
+        const label = `0x${address.toString(16).toUpperCase().padStart(3, '0')}`;
+        if (condition) {
+            const match = condition.match(/^(\w+)\s*(==|!=|<=|>=|<|>)\s*(\w+)$/);
+            const value = match ? Number(match[3]) : NaN;
+            if (!match || Number.isNaN(value) || !this.emulator.add_conditional_breakpoint(address, match[1], match[2], value)) {
+                this.showError(`Condition invalide: ${condition}`);
+                return;
+            }
+            info.textContent = `Breakpoint ${label} si ${condition}`;
+        } else {
+            this.emulator.add_breakpoint(address);
+            info.textContent = `Breakpoint ${label}`;
+        }
+    }
+
+    addWatchpoint() {
+        const address = this.parseAddress('breakpoint-address');
+        if (!this.emulator || address === null) {
+            this.showError('Adresse de watchpoint invalide');
+            return;
+        }
+
+        this.emulator.add_watchpoint(address, 1, 'write');
+        document.getElementById('debugger-info').textContent =
+            `Watchpoint écriture 0x${address.toString(16).toUpperCase().padStart(3, '0')}`;
+    }
+
     // ========== INTERFACE UTILISATEUR ==========
 
     updateStatus(status) {
@@ -5,6 +5,7 @@ const STATIC_ASSETS = [
     './index.html',
     './style.css',
     './main.js',
+    './audio-worklet.js',
     './pkg/ferris8.js',
     './pkg/ferris8_bg.wasm'
 ];